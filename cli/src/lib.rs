@@ -1,7 +1,15 @@
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
-use anyhow::Result;
-use clap::{command, Args, Parser, Subcommand};
+use anyhow::{bail, Result};
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use slop::{
+    book::{Book, Chapter, Manifest},
+    config::Config,
+    ical::Date,
+    menu::Clock,
+    prose::Style,
+    Errors, Severity,
+};
 use tokio::{fs, io::AsyncWriteExt};
 
 #[derive(Parser, Debug)]
@@ -17,10 +25,34 @@ enum Command {
     Fmt(FmtOpts),
     /// Check if a slop file is valid.
     Check(CheckOpts),
+    /// Check a slop file for likely mistakes.
+    Lint(LintOpts),
+    /// Apply automatic fixes to a slop file
+    Fix(FixOpts),
     /// Check if a slop file is valid.
     Title(TitleOpts),
     /// Render recipe to an svg file
     Render(RenderOpts),
+    /// Print when each step starts and ends, with the critical path marked by `*`
+    Timeline(TimelineOpts),
+    /// Print when to start each step of several recipes to serve them together
+    Plan(PlanOpts),
+    /// List the equipment each recipe needs
+    Equipment(EquipmentOpts),
+    /// Print recipes as an ingredient list and numbered instructions
+    Prose(ProseOpts),
+    /// Print the prep of each recipe that can be done ahead
+    Prep(PrepOpts),
+    /// Print the prep of several recipes with identical prep merged for batch cooking
+    Batch(BatchOpts),
+    /// Convert a recipe from another format into slop source
+    Import(ImportOpts),
+    /// Convert recipes into another format
+    Export(ExportOpts),
+    /// Build a printable cookbook from a directory of slop files or a book.toml manifest
+    Book(BookOpts),
+    /// Print the graph of ingredients and steps of each recipe
+    Graph(GraphOpts),
 }
 
 #[derive(Args, Debug)]
@@ -37,6 +69,29 @@ struct CheckOpts {
     file: PathBuf,
 }
 
+#[derive(Args, Debug)]
+struct LintOpts {
+    /// Path to slop file
+    #[arg()]
+    file: PathBuf,
+    /// Path to configuration file, defaults to the nearest slop.toml
+    #[arg(long)]
+    config: Option<PathBuf>,
+}
+
+#[derive(Args, Debug)]
+struct FixOpts {
+    /// Path to slop file
+    #[arg()]
+    file: PathBuf,
+    /// Write the fixed source back to the file instead of printing it
+    #[arg(long)]
+    write: bool,
+    /// Path to configuration file, defaults to the nearest slop.toml
+    #[arg(long)]
+    config: Option<PathBuf>,
+}
+
 #[derive(Args, Debug)]
 struct TitleOpts {
     /// Path to slop file
@@ -54,6 +109,179 @@ struct RenderOpts {
     out_file: PathBuf,
 }
 
+#[derive(Args, Debug)]
+struct TimelineOpts {
+    /// Path to slop file
+    #[arg()]
+    file: PathBuf,
+    /// Also render the timeline as a Gantt chart to this svg file
+    #[arg(long)]
+    svg: Option<PathBuf>,
+}
+
+#[derive(Args, Debug)]
+struct PlanOpts {
+    /// Paths to slop files, every recipe in them is part of the menu
+    #[arg(required = true)]
+    files: Vec<PathBuf>,
+    /// Time of day the menu is served, like 18:30 or 6:30pm
+    #[arg(long)]
+    serve: Clock,
+    /// Day the menu is served, like 2024-03-31, required for the iCalendar file
+    #[arg(long)]
+    date: Option<Date>,
+    /// Also export the plan as an iCalendar file with an alarm at the start of each step
+    #[arg(long, requires = "date")]
+    ics: Option<PathBuf>,
+    /// Leave steps without a duration out of the iCalendar file
+    #[arg(long)]
+    timed_only: bool,
+    /// Path to configuration file, defaults to the nearest slop.toml of the first file
+    #[arg(long)]
+    config: Option<PathBuf>,
+}
+
+#[derive(Args, Debug)]
+struct EquipmentOpts {
+    /// Path to slop file
+    #[arg()]
+    file: PathBuf,
+    /// Path to configuration file, defaults to the nearest slop.toml
+    #[arg(long)]
+    config: Option<PathBuf>,
+}
+
+#[derive(Args, Debug)]
+struct ProseOpts {
+    /// Path to slop file
+    #[arg()]
+    file: PathBuf,
+    /// Output format
+    #[arg(long, value_enum, default_value_t = ProseStyle::Text)]
+    style: ProseStyle,
+}
+
+#[derive(Args, Debug)]
+struct PrepOpts {
+    /// Path to slop file
+    #[arg()]
+    file: PathBuf,
+}
+
+#[derive(Args, Debug)]
+struct BatchOpts {
+    /// Paths to slop files, every recipe in them is part of the menu
+    #[arg(required = true)]
+    files: Vec<PathBuf>,
+}
+
+#[derive(Args, Debug)]
+struct ImportOpts {
+    /// Path to the recipe or collection of recipes to import
+    #[arg()]
+    file: PathBuf,
+    /// Format of the recipe
+    #[arg(long, value_enum, default_value_t = ImportFormat::SchemaOrg)]
+    from: ImportFormat,
+    /// Write each recipe to its own slop file in this directory along with a report of the
+    /// recipes that failed, instead of printing them. Existing slop files are kept, a recipe whose
+    /// name is taken gets a numbered file name
+    #[arg(long)]
+    out_dir: Option<PathBuf>,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug)]
+enum ImportFormat {
+    /// schema.org Recipe JSON-LD
+    SchemaOrg,
+    /// Cooklang
+    Cooklang,
+    /// Paprika `.paprikarecipes` archive
+    Paprika,
+    /// MealMaster text file with any number of recipes
+    MealMaster,
+    /// Plain text with a list of ingredients and directions
+    Text,
+}
+
+#[derive(Args, Debug)]
+struct ExportOpts {
+    /// Path to slop file
+    #[arg()]
+    file: PathBuf,
+    /// Output format
+    #[arg(long, value_enum, default_value_t = ExportFormat::SchemaOrg)]
+    to: ExportFormat,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug)]
+enum ExportFormat {
+    /// schema.org Recipe JSON-LD
+    SchemaOrg,
+    /// Cooklang, one recipe after the other
+    Cooklang,
+    /// Markdown with an ingredient table
+    Markdown,
+    /// Standalone HTML page with an ingredient table and a tree card
+    Html,
+}
+
+#[derive(Args, Debug)]
+struct BookOpts {
+    /// Directory of slop files, or a book.toml manifest listing them by chapter
+    #[arg()]
+    source: PathBuf,
+    /// Path to output file
+    #[arg()]
+    out_file: PathBuf,
+    /// Output format
+    #[arg(long, value_enum, default_value_t = BookFormat::Html)]
+    format: BookFormat,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug)]
+enum BookFormat {
+    /// Standalone HTML page with print styles
+    Html,
+    /// A4 PDF document
+    Pdf,
+}
+
+#[derive(Args, Debug)]
+struct GraphOpts {
+    /// Path to slop file
+    #[arg()]
+    file: PathBuf,
+    /// Output format
+    #[arg(long, value_enum, default_value_t = GraphFormat::Dot)]
+    format: GraphFormat,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug)]
+enum GraphFormat {
+    /// Graphviz DOT, one digraph per recipe
+    Dot,
+    /// Mermaid flowchart, one per recipe
+    Mermaid,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug)]
+enum ProseStyle {
+    Text,
+    Markdown,
+    Html,
+}
+
+impl From<ProseStyle> for Style {
+    fn from(style: ProseStyle) -> Self {
+        match style {
+            ProseStyle::Text => Style::Text,
+            ProseStyle::Markdown => Style::Markdown,
+            ProseStyle::Html => Style::Html,
+        }
+    }
+}
+
 pub async fn run() -> Result<()> {
     let args = Cli::parse();
     match args.command {
@@ -70,6 +298,39 @@ pub async fn run() -> Result<()> {
             eprint!("{errors}");
             Ok(())
         }
+        Command::Lint(opts) => {
+            let config = load_config(opts.config, &opts.file)?;
+            let source = fs::read_to_string(opts.file).await?;
+            let (file, errors) = slop::compile(&source);
+            let (syntax, _) = slop::parse(&source);
+            let diagnostics = Errors::from(
+                errors
+                    .into_iter()
+                    .chain(slop::lint::lint(&syntax, &file, &config.lint))
+                    .collect::<Vec<_>>(),
+            );
+            eprint!("{diagnostics}");
+            let failures = diagnostics
+                .iter()
+                .filter(|d| d.severity == Severity::Error)
+                .count();
+            if failures > 0 {
+                bail!("found {failures} errors");
+            }
+            Ok(())
+        }
+        Command::Fix(opts) => {
+            let config = load_config(opts.config, &opts.file)?;
+            let source = fs::read_to_string(&opts.file).await?;
+            let (fixed, diagnostics) = slop::fix::fix(&source, &config.lint);
+            eprint!("{}", Errors::from(diagnostics));
+            if opts.write {
+                fs::write(opts.file, fixed).await?;
+            } else {
+                print!("{fixed}");
+            }
+            Ok(())
+        }
         Command::Title(opts) => {
             let source = fs::read_to_string(opts.file).await?;
             let (ast, errors) = slop::parse(&source);
@@ -87,5 +348,281 @@ pub async fn run() -> Result<()> {
             }
             Ok(())
         }
+        Command::Timeline(opts) => {
+            let source = fs::read_to_string(opts.file).await?;
+            let (file, errors) = slop::compile(&source);
+            eprint!("{errors}");
+            for recipe in file.recipes() {
+                println!("{}", recipe.title().unwrap_or("Untitled"));
+                print!("{}", slop::timeline::timeline(recipe));
+            }
+            if let Some(out_file) = opts.svg {
+                let (svgs, _) = slop::to_timeline_svgs(&source);
+                let mut f = fs::File::create(out_file).await?;
+                for svg in svgs {
+                    f.write_all(svg.as_bytes()).await?;
+                }
+            }
+            Ok(())
+        }
+        Command::Plan(opts) => {
+            let config = load_config(opts.config, &opts.files[0])?;
+            let mut recipes = Vec::new();
+            for file in opts.files {
+                let source = fs::read_to_string(file).await?;
+                let (file, errors) = slop::compile(&source);
+                eprint!("{errors}");
+                recipes.extend(file.recipes);
+            }
+            let plan = slop::menu::schedule(&recipes, opts.serve);
+            print!("{plan}");
+            for conflict in slop::equipment::conflicts(&plan, &config.equipment) {
+                eprintln!("warning: {conflict}");
+            }
+            if let Some((out_file, date)) = opts.ics.zip(opts.date) {
+                fs::write(out_file, slop::ical::to_ical(&plan, date, opts.timed_only)).await?;
+            }
+            Ok(())
+        }
+        Command::Equipment(opts) => {
+            let config = load_config(opts.config, &opts.file)?;
+            let source = fs::read_to_string(opts.file).await?;
+            let (file, errors) = slop::compile(&source);
+            eprint!("{errors}");
+            for recipe in file.recipes() {
+                println!("{}", recipe.title().unwrap_or("Untitled"));
+                for equipment in slop::equipment::checklist(recipe, &config.equipment) {
+                    println!("- [ ] {}", equipment.name);
+                }
+            }
+            Ok(())
+        }
+        Command::Prose(opts) => {
+            let source = fs::read_to_string(opts.file).await?;
+            let (file, errors) = slop::compile(&source);
+            eprint!("{errors}");
+            let prose = match opts.style {
+                // A document rather than an article per recipe, so the ids of the cards are unique.
+                ProseStyle::Html => slop::html::to_html(file.recipes()),
+                style => {
+                    let recipes: Vec<String> = file
+                        .recipes()
+                        .iter()
+                        .map(|recipe| slop::prose::render(recipe, style.into()))
+                        .collect();
+                    recipes.join("\n")
+                }
+            };
+            print!("{prose}");
+            Ok(())
+        }
+        Command::Prep(opts) => {
+            let source = fs::read_to_string(opts.file).await?;
+            let (file, errors) = slop::compile(&source);
+            eprint!("{errors}");
+            for recipe in file.recipes() {
+                println!("{}", recipe.title().unwrap_or("Untitled"));
+                for group in slop::prep::prep_list(recipe) {
+                    println!("- {group}");
+                }
+            }
+            Ok(())
+        }
+        Command::Batch(opts) => {
+            let mut recipes = Vec::new();
+            for file in opts.files {
+                let source = fs::read_to_string(file).await?;
+                let (file, errors) = slop::compile(&source);
+                eprint!("{errors}");
+                recipes.extend(file.recipes);
+            }
+            for batch in slop::menu::consolidate_prep(&recipes) {
+                println!("- {batch}");
+            }
+            Ok(())
+        }
+        Command::Import(opts) => {
+            let name = opts
+                .file
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().to_string())
+                .unwrap_or_default();
+            let recipes: Vec<(String, Result<String, slop::import::Error>)> = match opts.from {
+                ImportFormat::SchemaOrg => {
+                    let source = fs::read_to_string(&opts.file).await?;
+                    vec![(name, Ok(slop::import::schema_org::import(&source)?))]
+                }
+                ImportFormat::Cooklang => {
+                    let source = fs::read_to_string(&opts.file).await?;
+                    vec![(name, Ok(slop::import::cooklang::import(&source)?))]
+                }
+                ImportFormat::Text => {
+                    let source = fs::read_to_string(&opts.file).await?;
+                    vec![(name, Ok(slop::import::text::import(&source)?))]
+                }
+                ImportFormat::Paprika => {
+                    let archive = fs::read(&opts.file).await?;
+                    slop::import::paprika::import(&archive)?
+                        .into_iter()
+                        .map(|imported| (imported.name, imported.result))
+                        .collect()
+                }
+                ImportFormat::MealMaster => {
+                    let source = fs::read_to_string(&opts.file).await?;
+                    slop::import::mealmaster::import(&source)?
+                        .into_iter()
+                        .map(|imported| (imported.name, imported.result))
+                        .collect()
+                }
+            };
+            let Some(out_dir) = opts.out_dir else {
+                let mut sources = Vec::new();
+                for (name, result) in recipes {
+                    match result {
+                        Ok(source) => sources.push(source),
+                        Err(err) => eprintln!("error: {name}: {err}"),
+                    }
+                }
+                print!("{}", sources.join("\n"));
+                return Ok(());
+            };
+            fs::create_dir_all(&out_dir).await?;
+            let total = recipes.len();
+            let mut written: Vec<String> = Vec::new();
+            let mut report = String::new();
+            for (name, result) in recipes {
+                match result {
+                    Ok(source) => {
+                        let file_name = unique_file_name(&name, &out_dir);
+                        fs::write(out_dir.join(&file_name), source).await?;
+                        written.push(file_name);
+                    }
+                    Err(err) => report += &format!("{name}: {err}\n"),
+                }
+            }
+            let summary = format!("imported {} of {total} recipes\n", written.len());
+            print!("{summary}");
+            if written.len() < total {
+                fs::write(out_dir.join("import-report.txt"), summary + &report).await?;
+                println!(
+                    "see {} for the failures",
+                    out_dir.join("import-report.txt").display()
+                );
+            }
+            if written.is_empty() {
+                bail!("no recipe was imported");
+            }
+            Ok(())
+        }
+        Command::Export(opts) => {
+            let source = fs::read_to_string(opts.file).await?;
+            let (file, errors) = slop::compile(&source);
+            eprint!("{errors}");
+            let exported = match opts.to {
+                ExportFormat::SchemaOrg => slop::schema_org::to_string(&file) + "\n",
+                ExportFormat::Cooklang => {
+                    let recipes: Vec<String> = file
+                        .recipes()
+                        .iter()
+                        .map(slop::cooklang::to_cooklang)
+                        .collect();
+                    recipes.join("\n")
+                }
+                ExportFormat::Markdown => {
+                    let recipes: Vec<String> = file
+                        .recipes()
+                        .iter()
+                        .map(slop::markdown::to_markdown)
+                        .collect();
+                    recipes.join("\n")
+                }
+                ExportFormat::Html => slop::html::to_html(file.recipes()),
+            };
+            print!("{exported}");
+            Ok(())
+        }
+        Command::Book(opts) => {
+            let manifest = if opts.source.is_dir() {
+                Manifest::discover(&opts.source)?
+            } else {
+                Manifest::load(&opts.source)?
+            };
+            let mut book = Book {
+                title: manifest.title.unwrap_or_else(|| "Recipes".to_string()),
+                chapters: Vec::new(),
+            };
+            for chapter in manifest.chapters {
+                let mut recipes = Vec::new();
+                for path in chapter.files {
+                    let source = fs::read_to_string(&path).await?;
+                    let (file, errors) = slop::compile(&source);
+                    if !errors.0.is_empty() {
+                        eprint!("{}: {errors}", path.display());
+                    }
+                    recipes.extend(file.recipes);
+                }
+                book.chapters.push(Chapter {
+                    title: chapter.title,
+                    recipes,
+                });
+            }
+            if book.recipes().next().is_none() {
+                bail!("no recipes found in {}", opts.source.display());
+            }
+            match opts.format {
+                BookFormat::Html => fs::write(opts.out_file, slop::book::to_html(&book)).await?,
+                BookFormat::Pdf => fs::write(opts.out_file, slop::book::pdf::to_pdf(&book)).await?,
+            }
+            Ok(())
+        }
+        Command::Graph(opts) => {
+            let source = fs::read_to_string(opts.file).await?;
+            let (file, errors) = slop::compile(&source);
+            eprint!("{errors}");
+            let graphs: Vec<String> = file
+                .recipes()
+                .iter()
+                .map(|recipe| match opts.format {
+                    GraphFormat::Dot => slop::graph::to_dot(recipe),
+                    GraphFormat::Mermaid => slop::graph::to_mermaid(recipe),
+                })
+                .collect();
+            print!("{}", graphs.join("\n"));
+            Ok(())
+        }
     }
 }
+
+// A name for the slop file of the recipe that no file in the directory has yet, e.g.
+// `banana_bread.slop`, so files of earlier imports or edited by hand are never overwritten.
+fn unique_file_name(name: &str, dir: &Path) -> String {
+    let stem = name
+        .to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>()
+        .join("_");
+    let stem = if stem.is_empty() {
+        "recipe".to_string()
+    } else {
+        stem
+    };
+    (1..)
+        .map(|n| match n {
+            1 => format!("{stem}.slop"),
+            n => format!("{stem}_{n}.slop"),
+        })
+        .find(|file_name| !dir.join(file_name).exists())
+        .expect("there is always a free name")
+}
+
+// Load the configuration file if given, otherwise the one nearest to the slop file.
+fn load_config(path: Option<PathBuf>, file: &Path) -> Result<Config> {
+    Ok(match path {
+        Some(path) => Config::load(&path)?,
+        None => {
+            let file = std::path::absolute(file)?;
+            Config::discover(file.parent().unwrap_or(&file))?
+        }
+    })
+}
@@ -34,8 +34,8 @@ async fn main() {
     run().await;
 }
 mod backend {
-    use log::info;
-    use std::collections::HashMap;
+    use log::{info, warn};
+    use std::{collections::HashMap, path::PathBuf};
 
     use codespan::{FileId, Files};
     use codespan_lsp::{byte_span_to_range, range_to_byte_span};
@@ -44,12 +44,18 @@ mod backend {
     use tower_lsp::lsp_types::*;
     use tower_lsp::{Client, LanguageServer};
 
-    use slop::{compile, format};
+    use slop::{
+        compile,
+        config::{self, Config},
+        format,
+    };
 
     #[derive(Debug)]
     struct State {
         sources: HashMap<Url, FileId>,
         files: Files<String>,
+        /// Configuration of the documents by their directory, dropped when a slop.toml changes.
+        configs: HashMap<PathBuf, Config>,
     }
 
     #[derive(Debug)]
@@ -65,6 +71,7 @@ mod backend {
                 state: Mutex::new(State {
                     sources: HashMap::new(),
                     files: Files::new(),
+                    configs: HashMap::new(),
                 }),
             }
         }
@@ -91,6 +98,7 @@ mod backend {
                     //}),
                     //hover_provider: Some(true),
                     document_formatting_provider: Some(true),
+                    code_action_provider: Some(CodeActionProviderCapability::Simple(true)),
                     //document_highlight_provider: Some(true),
                     //document_symbol_provider: Some(true),
                     //workspace_symbol_provider: Some(true),
@@ -100,6 +108,24 @@ mod backend {
             })
         }
 
+        async fn initialized(&self, _: InitializedParams) {
+            // Watch the configuration files to reload them when they change.
+            let options = DidChangeWatchedFilesRegistrationOptions {
+                watchers: vec![FileSystemWatcher {
+                    glob_pattern: format!("**/{}", config::FILE_NAME),
+                    kind: None,
+                }],
+            };
+            let registration = Registration {
+                id: "slop-config".to_string(),
+                method: "workspace/didChangeWatchedFiles".to_string(),
+                register_options: serde_json::to_value(options).ok(),
+            };
+            if let Err(e) = self.client.register_capability(vec![registration]).await {
+                warn!("failed to watch {}: {}", config::FILE_NAME, e);
+            }
+        }
+
         async fn shutdown(&self) -> Result<()> {
             Ok(())
         }
@@ -108,7 +134,7 @@ mod backend {
             info!("did_open {}", &params.text_document.uri);
             let mut state = self.state.lock().await;
             let id = get_or_insert_source(&mut state, &params.text_document);
-            let diags = get_diagnostics(&state, &params.text_document.uri, id);
+            let diags = get_diagnostics(&mut state, &params.text_document.uri, id);
             self.client
                 .publish_diagnostics(params.text_document.uri, diags, None)
                 .await;
@@ -117,7 +143,7 @@ mod backend {
         async fn did_change(&self, params: DidChangeTextDocumentParams) {
             let mut state = self.state.lock().await;
             let id = reload_source(&mut state, &params.text_document, params.content_changes);
-            let diags = get_diagnostics(&state, &params.text_document.uri, id);
+            let diags = get_diagnostics(&mut state, &params.text_document.uri, id);
             self.client
                 .publish_diagnostics(params.text_document.uri, diags, None)
                 .await;
@@ -125,11 +151,35 @@ mod backend {
         async fn did_save(&self, params: DidSaveTextDocumentParams) {
             let mut state = self.state.lock().await;
             let id = save_source(&mut state, &params.text_document, &params.text);
-            let diags = get_diagnostics(&state, &params.text_document.uri, id);
+            let diags = get_diagnostics(&mut state, &params.text_document.uri, id);
             self.client
                 .publish_diagnostics(params.text_document.uri, diags, None)
                 .await;
         }
+        async fn did_change_watched_files(&self, params: DidChangeWatchedFilesParams) {
+            let config_changed = params.changes.iter().any(|change| {
+                change
+                    .uri
+                    .path_segments()
+                    .and_then(|mut segments| segments.next_back())
+                    == Some(config::FILE_NAME)
+            });
+            if !config_changed {
+                return;
+            }
+            info!("reloading configuration");
+            let mut state = self.state.lock().await;
+            state.configs.clear();
+            let sources: Vec<(Url, FileId)> = state
+                .sources
+                .iter()
+                .map(|(uri, id)| (uri.clone(), *id))
+                .collect();
+            for (uri, id) in sources {
+                let diags = get_diagnostics(&mut state, &uri, id);
+                self.client.publish_diagnostics(uri, diags, None).await;
+            }
+        }
         async fn formatting(
             &self,
             params: DocumentFormattingParams,
@@ -151,6 +201,24 @@ mod backend {
                 Err(Error::invalid_request())
             }
         }
+        async fn code_action(
+            &self,
+            params: CodeActionParams,
+        ) -> Result<Option<CodeActionResponse>> {
+            let mut state = self.state.lock().await;
+            if let Some(id) = state.sources.get(&params.text_document.uri).copied() {
+                let actions =
+                    get_code_actions(&mut state, &params.text_document.uri, id, params.range);
+                Ok(Some(
+                    actions
+                        .into_iter()
+                        .map(CodeActionOrCommand::CodeAction)
+                        .collect(),
+                ))
+            } else {
+                Err(Error::invalid_request())
+            }
+        }
     }
 
     fn get_or_insert_source(state: &mut State, document: &TextDocumentItem) -> FileId {
@@ -217,67 +285,129 @@ mod backend {
         }
     }
 
-    fn get_diagnostics(state: &State, _uri: &Url, id: FileId) -> Vec<Diagnostic> {
+    fn get_diagnostics(state: &mut State, uri: &Url, id: FileId) -> Vec<Diagnostic> {
+        let config = load_config(state, uri);
+        let state = &*state;
+        diagnose(state, &config, id)
+            .iter()
+            .map(|diagnostic| convert_diagnostic(state, uri, id, diagnostic))
+            .collect()
+    }
+
+    // Compile and lint the source, attaching fixes to the diagnostics.
+    fn diagnose(state: &State, config: &Config, id: FileId) -> Vec<slop::Diagnostic> {
         let source = state.files.source(id);
-        let (_, errors) = compile(source);
-        errors
+        let (file, errors) = compile(source);
+        let (syntax, _) = slop::parse(source);
+        let mut diagnostics: Vec<slop::Diagnostic> = errors
             .into_iter()
-            .map(|err| match err {
-                slop::Error::ParseError(slop::ParseError::UnexpectedToken(token, position)) => {
-                    let range = byte_span_to_range(&state.files, id, position).unwrap();
-                    Diagnostic {
-                        range: convert_range(range),
-                        severity: Some(DiagnosticSeverity::Error),
-                        code: None,
-                        source: None,
-                        message: format!("unexpected token: {token}"),
-                        related_information: None,
-                        tags: None,
-                    }
-                }
-                slop::Error::ParseError(slop::ParseError::UnexpectedEOF) => {
-                    let range =
-                        byte_span_to_range(&state.files, id, source.len() - 1..source.len())
-                            .unwrap();
-                    Diagnostic {
-                        range: convert_range(range),
-                        severity: Some(DiagnosticSeverity::Error),
-                        code: None,
-                        source: None,
-                        message: "unexpected end of file".to_string(),
-                        related_information: None,
-                        tags: None,
-                    }
-                }
-                slop::Error::CompilationError(slop::CompilationError::MissingOperand(position)) => {
-                    let range = byte_span_to_range(&state.files, id, position).unwrap();
-                    Diagnostic {
-                        range: convert_range(range),
-                        severity: Some(DiagnosticSeverity::Error),
-                        code: None,
-                        source: None,
-                        message: "missing operand".to_string(),
-                        related_information: None,
-                        tags: None,
-                    }
-                }
-                slop::Error::CompilationError(slop::CompilationError::UnusedOperands(
-                    count,
-                    position,
-                )) => {
-                    let range = byte_span_to_range(&state.files, id, position).unwrap();
-                    Diagnostic {
-                        range: convert_range(range),
-                        severity: Some(DiagnosticSeverity::Error),
-                        code: None,
-                        source: None,
-                        message: format!("found {count} unused operands"),
-                        related_information: None,
-                        tags: None,
-                    }
-                }
+            .chain(slop::lint::lint(&syntax, &file, &config.lint))
+            .collect();
+        slop::fix::suggest(source, &file, &mut diagnostics);
+        diagnostics
+    }
+
+    fn get_code_actions(state: &mut State, uri: &Url, id: FileId, range: Range) -> Vec<CodeAction> {
+        let config = load_config(state, uri);
+        let state = &*state;
+        let mut actions = Vec::new();
+        for diagnostic in diagnose(state, &config, id) {
+            let diagnostic_range = convert_span(state, id, diagnostic.span().clone());
+            if diagnostic_range.end < range.start || range.end < diagnostic_range.start {
+                continue;
+            }
+            let lsp_diagnostic = convert_diagnostic(state, uri, id, &diagnostic);
+            for fix in &diagnostic.fixes {
+                let edits = fix
+                    .edits
+                    .iter()
+                    .map(|edit| TextEdit {
+                        range: convert_span(state, id, edit.span.clone()),
+                        new_text: edit.text.clone(),
+                    })
+                    .collect();
+                actions.push(CodeAction {
+                    title: fix.message.clone(),
+                    kind: Some(CodeActionKind::QUICKFIX),
+                    diagnostics: Some(vec![lsp_diagnostic.clone()]),
+                    edit: Some(WorkspaceEdit::new(HashMap::from([(uri.clone(), edits)]))),
+                    ..CodeAction::default()
+                });
+            }
+        }
+        actions
+    }
+
+    // Load the slop.toml nearest to the document, falling back to the default configuration.
+    // Configurations are cached by directory until a slop.toml changes.
+    fn load_config(state: &mut State, uri: &Url) -> Config {
+        let path = match uri.to_file_path() {
+            Ok(path) => path,
+            Err(_) => return Config::default(),
+        };
+        let dir = path.parent().unwrap_or(&path).to_owned();
+        state
+            .configs
+            .entry(dir)
+            .or_insert_with_key(|dir| {
+                Config::discover(dir).unwrap_or_else(|e| {
+                    warn!("failed to load configuration for {}: {}", uri, e);
+                    Config::default()
+                })
             })
-            .collect()
+            .clone()
+    }
+
+    fn convert_diagnostic(
+        state: &State,
+        uri: &Url,
+        id: FileId,
+        diagnostic: &slop::Diagnostic,
+    ) -> Diagnostic {
+        let mut message = diagnostic.message.clone();
+        for note in &diagnostic.notes {
+            message.push_str(&format!("\nnote: {note}"));
+        }
+        for help in &diagnostic.help {
+            message.push_str(&format!("\nhelp: {help}"));
+        }
+        let related_information = diagnostic
+            .labels
+            .iter()
+            .map(|label| DiagnosticRelatedInformation {
+                location: Location {
+                    uri: uri.clone(),
+                    range: convert_span(state, id, label.span.clone()),
+                },
+                message: label.message.clone(),
+            })
+            .collect::<Vec<DiagnosticRelatedInformation>>();
+        Diagnostic {
+            range: convert_span(state, id, diagnostic.span().clone()),
+            severity: Some(match diagnostic.severity {
+                slop::Severity::Error => DiagnosticSeverity::Error,
+                slop::Severity::Warning => DiagnosticSeverity::Warning,
+                slop::Severity::Hint => DiagnosticSeverity::Hint,
+            }),
+            code: Some(NumberOrString::String(diagnostic.code.to_string())),
+            source: Some("slop".to_string()),
+            message,
+            related_information: if related_information.is_empty() {
+                None
+            } else {
+                Some(related_information)
+            },
+            tags: None,
+        }
+    }
+
+    // Convert a byte span into a range, clamping it to the source.
+    fn convert_span(state: &State, id: FileId, span: std::ops::Range<usize>) -> Range {
+        let len = state.files.source(id).len();
+        let start = span.start.min(len);
+        let end = span.end.clamp(start, len);
+        let range = byte_span_to_range(&state.files, id, start..end).unwrap_or_default();
+        convert_range(range)
     }
 
     fn convert_range(r: lsp_types::Range) -> Range {
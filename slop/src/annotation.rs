@@ -0,0 +1,325 @@
+use std::{
+    fmt::Display,
+    ops::{Add, AddAssign},
+};
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+use crate::{ast::Position, semantic::Operand};
+
+// Structured annotations extracted from the free text of operators.
+//
+// Operator texts such as `bake 375F 10m` or `cover cook 5m on medium low heat` carry durations,
+// temperatures and heat levels. They are extracted when the semantic tree is built and stored on
+// `Operand::Operator`. Spans are byte offsets into the operator text rather than the source, as
+// operators built with `edit` have no source, `Annotation::source_span` maps them into the source.
+
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub struct Annotation {
+    /// Span of the annotation within the operator text, see [`Annotation::source_span`].
+    pub span: Position,
+    pub kind: AnnotationKind,
+}
+
+impl Annotation {
+    /// The span of the annotation in `src`, the source of the `operator` it was found in.
+    ///
+    /// Returns `None` when the operator doesn't come from `src`, e.g. when it was built with
+    /// `edit`.
+    pub fn source_span(&self, src: &str, operator: &Operand) -> Option<Position> {
+        let position = operator.position();
+        // The text follows the `=` or `#` of the operator and the spaces after it.
+        let start = position.start + src.get(position.clone())?.find(operator.text()?)?;
+        Some(start + self.span.start..start + self.span.end)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub enum AnnotationKind {
+    Duration(Duration),
+    Temperature(Temperature),
+    Heat(Heat),
+}
+
+/// A length of time, written as a range like `10-12 minutes` or a single value where `min` and
+/// `max` are equal.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Duration {
+    pub min: std::time::Duration,
+    pub max: std::time::Duration,
+}
+
+impl Duration {
+    pub fn from_secs(secs: u64) -> Self {
+        let secs = std::time::Duration::from_secs(secs);
+        Self {
+            min: secs,
+            max: secs,
+        }
+    }
+    pub fn is_zero(&self) -> bool {
+        self.max.is_zero()
+    }
+}
+
+impl From<std::time::Duration> for Duration {
+    fn from(duration: std::time::Duration) -> Self {
+        Self {
+            min: duration,
+            max: duration,
+        }
+    }
+}
+
+impl Add for Duration {
+    type Output = Duration;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Self {
+            min: self.min + rhs.min,
+            max: self.max + rhs.max,
+        }
+    }
+}
+
+impl AddAssign for Duration {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl std::iter::Sum for Duration {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Duration::default(), Add::add)
+    }
+}
+
+impl Display for Duration {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        fn write_secs(f: &mut std::fmt::Formatter<'_>, secs: u64) -> std::fmt::Result {
+            let (h, m, s) = (secs / 3600, secs / 60 % 60, secs % 60);
+            let mut parts = Vec::new();
+            if h > 0 {
+                parts.push(format!("{h}h"));
+            }
+            if m > 0 {
+                parts.push(format!("{m}m"));
+            }
+            if s > 0 || parts.is_empty() {
+                parts.push(format!("{s}s"));
+            }
+            write!(f, "{}", parts.join(" "))
+        }
+        write_secs(f, self.min.as_secs())?;
+        if self.max != self.min {
+            write!(f, "-")?;
+            write_secs(f, self.max.as_secs())?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Temperature {
+    pub value: f64,
+    pub scale: Scale,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Scale {
+    Fahrenheit,
+    Celsius,
+}
+
+impl Temperature {
+    pub fn fahrenheit(&self) -> f64 {
+        match self.scale {
+            Scale::Fahrenheit => self.value,
+            Scale::Celsius => self.value * 9.0 / 5.0 + 32.0,
+        }
+    }
+    pub fn celsius(&self) -> f64 {
+        match self.scale {
+            Scale::Fahrenheit => (self.value - 32.0) * 5.0 / 9.0,
+            Scale::Celsius => self.value,
+        }
+    }
+}
+
+impl Display for Temperature {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.scale {
+            Scale::Fahrenheit => write!(f, "{}°F", self.value),
+            Scale::Celsius => write!(f, "{}°C", self.value),
+        }
+    }
+}
+
+/// Heat level of a stove top.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Heat {
+    Low,
+    MediumLow,
+    Medium,
+    MediumHigh,
+    High,
+}
+
+impl Display for Heat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Heat::Low => write!(f, "low"),
+            Heat::MediumLow => write!(f, "medium low"),
+            Heat::Medium => write!(f, "medium"),
+            Heat::MediumHigh => write!(f, "medium high"),
+            Heat::High => write!(f, "high"),
+        }
+    }
+}
+
+/// Time a recipe takes, split into time spent working and time spent waiting.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Times {
+    pub active: Duration,
+    pub passive: Duration,
+}
+
+impl Times {
+    pub fn total(&self) -> Duration {
+        self.active + self.passive
+    }
+}
+
+static DURATION: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r"(?i)\b(\d+(?:\.\d+)?)(?:\s*(?:-|to)\s*(\d+(?:\.\d+)?))?\s*(hours?|hrs?|h|minutes?|mins?|m|seconds?|secs?|s)\b",
+    )
+    .unwrap()
+});
+static TEMPERATURE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r"\b(\d+(?:\.\d+)?)\s*(?:°\s*([FCfc])|(?i:degrees?|deg)\b(?:\s*([FCfc])\b)?|([FC])\b)",
+    )
+    .unwrap()
+});
+static HEAT: Lazy<Regex> = Lazy::new(|| {
+    let level = r"(med(?:ium)?[\s-]+low|med(?:ium)?[\s-]+high|med(?:ium)?|low|high)";
+    Regex::new(&format!(
+        r"(?i)\bon\s+{level}\b(?:\s+heat\b)?|\b{level}\s+heat\b"
+    ))
+    .unwrap()
+});
+// Words of steps that mostly involve waiting rather than working.
+static PASSIVE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r"(?i)\b(bake|baked|roast|simmer|rest|chill|refrigerate|freeze|rise|proof|marinate|soak|steep|cool|steam|braise|boil|cover|let|wait|set aside)\b",
+    )
+    .unwrap()
+});
+
+/// Extract the annotations of an operator text, ordered by their position in the text.
+pub fn annotate(text: &str) -> Vec<Annotation> {
+    let mut annotations = Vec::new();
+    for captures in TEMPERATURE.captures_iter(text) {
+        let value: f64 = captures[1].parse().unwrap_or_default();
+        let scale = match captures
+            .get(2)
+            .or(captures.get(3))
+            .or(captures.get(4))
+            .map(|m| m.as_str())
+        {
+            Some("C" | "c") => Scale::Celsius,
+            _ => Scale::Fahrenheit,
+        };
+        // Skip numbers that are unlikely to be cooking temperatures, e.g. `2 C` meaning cups.
+        let plausible = match scale {
+            Scale::Fahrenheit => (100.0..=600.0).contains(&value),
+            Scale::Celsius => (30.0..=320.0).contains(&value),
+        };
+        if !plausible {
+            continue;
+        }
+        annotations.push(Annotation {
+            span: captures.get(0).unwrap().range(),
+            kind: AnnotationKind::Temperature(Temperature { value, scale }),
+        });
+    }
+    let mut durations: Vec<Annotation> = Vec::new();
+    for captures in DURATION.captures_iter(text) {
+        let span = captures.get(0).unwrap().range();
+        if overlaps(&annotations, &span) {
+            continue;
+        }
+        let unit = match captures[3].to_lowercase().chars().next() {
+            Some('h') => 3600.0,
+            Some('m') => 60.0,
+            _ => 1.0,
+        };
+        let secs = |value: &str| {
+            let value: f64 = value.parse().unwrap_or_default();
+            std::time::Duration::from_secs((value * unit).round() as u64)
+        };
+        let min = secs(&captures[1]);
+        let max = captures.get(2).map_or(min, |m| secs(m.as_str()));
+        let duration = Duration { min, max };
+        // Join compound durations such as `1h 30m` or `1 hour and 30 minutes`.
+        if let Some(Annotation {
+            span: previous,
+            kind: AnnotationKind::Duration(total),
+        }) = durations.last_mut()
+        {
+            let between = text[previous.end..span.start].trim();
+            if between.is_empty() || between.eq_ignore_ascii_case("and") {
+                previous.end = span.end;
+                *total += duration;
+                continue;
+            }
+        }
+        durations.push(Annotation {
+            span,
+            kind: AnnotationKind::Duration(duration),
+        });
+    }
+    annotations.extend(durations);
+    for captures in HEAT.captures_iter(text) {
+        let level = captures.get(1).or(captures.get(2)).unwrap().as_str();
+        let level = level.to_lowercase();
+        let heat = if level.ends_with("low") && level.starts_with("med") {
+            Heat::MediumLow
+        } else if level.ends_with("high") && level.starts_with("med") {
+            Heat::MediumHigh
+        } else if level.starts_with("med") {
+            Heat::Medium
+        } else if level == "low" {
+            Heat::Low
+        } else {
+            Heat::High
+        };
+        annotations.push(Annotation {
+            span: captures.get(0).unwrap().range(),
+            kind: AnnotationKind::Heat(heat),
+        });
+    }
+    annotations.sort_by_key(|annotation| annotation.span.start);
+    annotations
+}
+
+/// Reports whether a step mostly involves waiting, e.g. baking or chilling, rather than work.
+pub fn is_passive(text: &str) -> bool {
+    PASSIVE.is_match(text)
+}
+
+fn overlaps(annotations: &[Annotation], span: &Position) -> bool {
+    annotations
+        .iter()
+        .any(|a| a.span.start < span.end && span.start < a.span.end)
+}
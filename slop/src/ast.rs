@@ -9,11 +9,13 @@ pub trait Positioned {
 }
 
 #[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SourceFile {
     pub recipes: Vec<Recipe>,
 }
 
 #[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Recipe {
     pub position: Position,
     pub title: Option<String>,
@@ -22,6 +24,8 @@ pub struct Recipe {
     pub root: Operand,
 }
 #[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
 pub enum Operand {
     Ingredient {
         position: Position,
@@ -63,6 +67,8 @@ impl Positioned for Operand {
 }
 
 #[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
 pub enum Quantity {
     Number(String),
     Fraction(String),
@@ -0,0 +1,295 @@
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
+
+use thiserror::Error;
+
+use crate::{html, menu::normalize_ingredient, prose::escape_html, semantic::Recipe};
+
+#[cfg(feature = "pdf")]
+pub mod pdf;
+
+// Printable cookbooks of many recipes.
+//
+// A book is a title and chapters of recipes. It is laid out as a title page, a table of contents,
+// the chapters with an article and a tree card per recipe as in `html`, and an index of the
+// ingredients linking to the recipes using them. The print style sheet starts the contents, each
+// chapter and the index on a new page and never breaks a page inside a card.
+//
+// The recipes of a book are listed in a `book.toml` manifest next to them, e.g.
+//
+//     title = "Family Cookbook"
+//
+//     [[chapters]]
+//     title = "Breakfast"
+//     recipes = ["pancakes.slop", "eggs"]
+//
+// where paths are relative to the manifest and a directory stands for the `.slop` files in it.
+// Without a manifest the `.slop` files of a directory are a book on their own: the files at its
+// top are the first chapter, without a title, and each subdirectory is a chapter named after it.
+
+/// Name of the manifest file.
+pub const MANIFEST: &str = "book.toml";
+
+#[derive(Error, Debug)]
+#[non_exhaustive]
+pub enum Error {
+    #[error("reading {0}: {1}")]
+    Io(PathBuf, std::io::Error),
+    #[error("invalid TOML: {0}")]
+    Toml(#[from] toml::de::Error),
+    #[error("`title` must be a string")]
+    InvalidTitle,
+    #[error("invalid chapter {0}, expected a table with a `recipes` list of paths and an optional `title`")]
+    InvalidChapter(usize),
+}
+
+type Result<T> = std::result::Result<T, Error>;
+
+/// The files of a book, see the module comment for where they come from.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Manifest {
+    pub title: Option<String>,
+    pub chapters: Vec<ManifestChapter>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ManifestChapter {
+    pub title: Option<String>,
+    pub files: Vec<PathBuf>,
+}
+
+impl Manifest {
+    /// Parse a manifest, the paths of the recipes are kept as they are written.
+    pub fn from_toml(src: &str) -> Result<Self> {
+        let table: toml::Table = src.parse()?;
+        let title = match table.get("title") {
+            Some(title) => Some(title.as_str().ok_or(Error::InvalidTitle)?.to_string()),
+            None => None,
+        };
+        let chapters = match table.get("chapters") {
+            Some(chapters) => chapters.as_array().ok_or(Error::InvalidChapter(1))?,
+            None => {
+                return Ok(Manifest {
+                    title,
+                    chapters: Vec::new(),
+                })
+            }
+        };
+        let chapters = chapters
+            .iter()
+            .enumerate()
+            .map(|(i, chapter)| {
+                let invalid = || Error::InvalidChapter(i + 1);
+                let chapter = chapter.as_table().ok_or_else(invalid)?;
+                let title = match chapter.get("title") {
+                    Some(title) => Some(title.as_str().ok_or_else(invalid)?.to_string()),
+                    None => None,
+                };
+                let files = chapter
+                    .get("recipes")
+                    .and_then(toml::Value::as_array)
+                    .ok_or_else(invalid)?
+                    .iter()
+                    .map(|path| path.as_str().map(PathBuf::from).ok_or_else(invalid))
+                    .collect::<Result<Vec<_>>>()?;
+                Ok(ManifestChapter { title, files })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Manifest { title, chapters })
+    }
+
+    /// Load a manifest, resolving the paths of the recipes against its directory and replacing
+    /// directories with the `.slop` files in them.
+    pub fn load(path: &Path) -> Result<Self> {
+        let src = std::fs::read_to_string(path).map_err(|e| Error::Io(path.to_owned(), e))?;
+        let mut manifest = Self::from_toml(&src)?;
+        let dir = path.parent().unwrap_or(Path::new(""));
+        for chapter in &mut manifest.chapters {
+            let mut files = Vec::new();
+            for file in &chapter.files {
+                let file = dir.join(file);
+                if file.is_dir() {
+                    files.extend(slop_files(&file)?);
+                } else {
+                    files.push(file);
+                }
+            }
+            chapter.files = files;
+        }
+        Ok(manifest)
+    }
+
+    /// The book of a directory, from its manifest if it has one.
+    pub fn discover(dir: &Path) -> Result<Self> {
+        let manifest = dir.join(MANIFEST);
+        if manifest.is_file() {
+            return Self::load(&manifest);
+        }
+        let mut chapters = vec![ManifestChapter {
+            title: None,
+            files: slop_files(dir)?,
+        }];
+        for subdir in entries(dir)?.into_iter().filter(|path| path.is_dir()) {
+            chapters.push(ManifestChapter {
+                title: subdir
+                    .file_name()
+                    .map(|name| title_case(&name.to_string_lossy())),
+                files: slop_files(&subdir)?,
+            });
+        }
+        chapters.retain(|chapter| !chapter.files.is_empty());
+        let title = std::path::absolute(dir).ok().and_then(|dir| {
+            dir.file_name()
+                .map(|name| title_case(&name.to_string_lossy()))
+        });
+        Ok(Manifest { title, chapters })
+    }
+}
+
+// The entries of the directory in order of their names.
+fn entries(dir: &Path) -> Result<Vec<PathBuf>> {
+    let io = |e| Error::Io(dir.to_owned(), e);
+    let mut entries = std::fs::read_dir(dir)
+        .map_err(io)?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<std::io::Result<Vec<_>>>()
+        .map_err(io)?;
+    entries.sort();
+    Ok(entries)
+}
+
+fn slop_files(dir: &Path) -> Result<Vec<PathBuf>> {
+    let mut files = entries(dir)?;
+    files.retain(|path| path.is_file() && path.extension().is_some_and(|ext| ext == "slop"));
+    Ok(files)
+}
+
+// A title for a file name, e.g. `main_dishes` becomes `Main dishes`.
+fn title_case(name: &str) -> String {
+    let name = name.replace(['_', '-'], " ");
+    let mut chars = name.trim().chars();
+    chars
+        .next()
+        .map(|first| first.to_uppercase().chain(chars).collect())
+        .unwrap_or_default()
+}
+
+/// A book of compiled recipes.
+#[derive(Clone, Debug, Default)]
+pub struct Book {
+    pub title: String,
+    pub chapters: Vec<Chapter>,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct Chapter {
+    /// Chapters without a title are listed without a heading, e.g. the loose recipes before the
+    /// first subdirectory.
+    pub title: Option<String>,
+    pub recipes: Vec<Recipe>,
+}
+
+impl Book {
+    /// The recipes of every chapter in order, their position is their number in the index.
+    pub fn recipes(&self) -> impl Iterator<Item = &Recipe> {
+        self.chapters.iter().flat_map(|chapter| &chapter.recipes)
+    }
+
+    /// The ingredients in alphabetical order with the numbers of the recipes using them.
+    ///
+    /// Ingredients are merged by their lowercase singular name without the notes after a comma,
+    /// so `Eggs, beaten` and `egg` are the same entry, which is named as first written, here
+    /// `Eggs`. Derived ingredients are left out.
+    pub fn index(&self) -> Vec<(String, Vec<usize>)> {
+        let mut index: BTreeMap<String, (String, Vec<usize>)> = BTreeMap::new();
+        for (number, recipe) in self.recipes().enumerate() {
+            for ingredient in recipe.ingredients() {
+                if ingredient.is_derived() {
+                    continue;
+                }
+                let name = ingredient
+                    .text()
+                    .split([',', '('])
+                    .next()
+                    .unwrap_or_default()
+                    .trim();
+                let key = normalize_ingredient(name);
+                if key.is_empty() {
+                    continue;
+                }
+                let (_, recipes) = index
+                    .entry(key)
+                    .or_insert_with(|| (name.to_string(), Vec::new()));
+                if recipes.last() != Some(&number) {
+                    recipes.push(number);
+                }
+            }
+        }
+        index.into_values().collect()
+    }
+}
+
+const STYLE: &str = "\
+.title-page { text-align: center; padding-top: 30vh; }
+.contents ol { list-style: none; }
+.index dd { margin: 0 0 0.5em 2em; }
+.card { break-inside: avoid; }
+@page { size: A4; margin: 2cm; }
+@media print { .contents, .chapter, .index { break-before: page; } a { color: inherit; text-decoration: none; } }
+";
+
+/// Render the book as a standalone HTML document for printing.
+pub fn to_html(book: &Book) -> String {
+    let recipe_id = |number: usize| format!("recipe-{}", number + 1);
+    let titles: Vec<String> = book
+        .recipes()
+        .map(|recipe| escape_html(recipe.title().unwrap_or("Untitled")))
+        .collect();
+    let link = |number: usize| format!("<a href=\"#{}\">{}</a>", recipe_id(number), titles[number]);
+
+    let mut contents = String::from("<nav class=\"contents\">\n<h1>Contents</h1>\n<ol>\n");
+    let mut chapters = String::new();
+    let mut number = 0;
+    for (i, chapter) in book.chapters.iter().enumerate() {
+        let id = format!("chapter-{}", i + 1);
+        chapters += &format!("<section class=\"chapter\" id=\"{id}\">\n");
+        if let Some(title) = &chapter.title {
+            contents += &format!("<li><a href=\"#{id}\">{}</a>\n<ol>\n", escape_html(title));
+            chapters += &format!("<h1>{}</h1>\n", escape_html(title));
+        }
+        for recipe in &chapter.recipes {
+            contents += &format!("<li>{}</li>\n", link(number));
+            chapters += &html::article(recipe, &recipe_id(number));
+            number += 1;
+        }
+        if chapter.title.is_some() {
+            contents += "</ol>\n</li>\n";
+        }
+        chapters += "</section>\n";
+    }
+    contents += "</ol>\n</nav>\n";
+
+    let mut index =
+        String::from("<section class=\"index\">\n<h1>Index of ingredients</h1>\n<dl>\n");
+    for (ingredient, recipes) in book.index() {
+        let links: Vec<String> = recipes.into_iter().map(link).collect();
+        index += &format!(
+            "<dt>{}</dt>\n<dd>{}</dd>\n",
+            escape_html(&ingredient),
+            links.join(", ")
+        );
+    }
+    index += "</dl>\n</section>\n";
+
+    let title = format!(
+        "<header class=\"title-page\">\n<h1>{}</h1>\n</header>\n",
+        escape_html(&book.title)
+    );
+    html::document(
+        &book.title,
+        STYLE,
+        &(title + &contents + &chapters + &index),
+    )
+}
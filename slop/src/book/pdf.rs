@@ -0,0 +1,472 @@
+use pdf_writer::{Content, Finish, Name, Pdf, Rect, Ref, Str, TextStr};
+
+use super::Book;
+use crate::{html, prose, semantic::Recipe};
+
+// Rendering of books as PDF.
+//
+// Pages are A4 and text is set in the standard Helvetica fonts of every PDF reader, so no font is
+// embedded and text is limited to the Windows-1252 characters. Lines are wrapped with the widths
+// of Helvetica and a new page starts when the next line doesn't fit. Every chapter and recipe
+// starts on a new page. Cards are drawn as boxes with the layout of the table in `html`: a card
+// that doesn't fit on the rest of the page moves to the next one and a card larger than a page is
+// scaled down, so a card is never split. The contents and the index refer to page numbers.
+
+// Size of A4 paper and the margins around the text, in points.
+const WIDTH: f32 = 595.0;
+const HEIGHT: f32 = 842.0;
+const MARGIN: f32 = 56.0;
+// Space between the border of a card cell and its text.
+const PADDING: f32 = 3.0;
+// Text of card cells is wrapped at this width.
+const MAX_CELL: f32 = 140.0;
+const CARD_SIZE: f32 = 9.0;
+const TEXT_SIZE: f32 = 10.0;
+
+/// Render the book as a PDF document.
+pub fn to_pdf(book: &Book) -> Vec<u8> {
+    let mut body = Layout::default();
+    let mut chapter_pages = Vec::new();
+    let mut recipe_pages = Vec::new();
+    for chapter in &book.chapters {
+        body.new_page();
+        chapter_pages.push(body.page());
+        if let Some(title) = &chapter.title {
+            body.paragraph(MARGIN, title, 22.0, Font::Bold);
+            body.space(12.0);
+        }
+        for (i, recipe) in chapter.recipes.iter().enumerate() {
+            if i > 0 {
+                body.new_page();
+            }
+            recipe_pages.push(body.page());
+            recipe_body(&mut body, recipe);
+        }
+    }
+
+    // The contents take the same number of pages whatever the page numbers in them are, so they
+    // are laid out once to count their pages and once more with the numbers.
+    let contents = |offset: usize| {
+        let mut front = Layout::default();
+        front.new_page();
+        let title_width = text_width(&book.title, 28.0, Font::Bold);
+        front.y = HEIGHT * 2.0 / 3.0;
+        front.text(
+            (WIDTH - title_width).max(MARGIN * 2.0) / 2.0,
+            &book.title,
+            28.0,
+            Font::Bold,
+        );
+        front.new_page();
+        front.paragraph(MARGIN, "Contents", 22.0, Font::Bold);
+        front.space(12.0);
+        let mut recipes = book.recipes().zip(&recipe_pages);
+        for (chapter, page) in book.chapters.iter().zip(&chapter_pages) {
+            let indent = match &chapter.title {
+                Some(title) => {
+                    front.space(4.0);
+                    front.entry(MARGIN, title, offset + page + 1, Font::Bold);
+                    12.0
+                }
+                None => 0.0,
+            };
+            for (recipe, page) in recipes.by_ref().take(chapter.recipes.len()) {
+                let title = recipe.title().unwrap_or("Untitled");
+                front.entry(MARGIN + indent, title, offset + page + 1, Font::Regular);
+            }
+        }
+        front
+    };
+    let front_pages = contents(0).pages.len();
+    let front = contents(front_pages);
+
+    body.new_page();
+    body.paragraph(MARGIN, "Index of ingredients", 22.0, Font::Bold);
+    body.space(12.0);
+    for (ingredient, recipes) in book.index() {
+        let pages: Vec<String> = recipes
+            .into_iter()
+            .map(|number| (front_pages + recipe_pages[number] + 1).to_string())
+            .collect();
+        let entry = format!("{ingredient}, {}", pages.join(", "));
+        body.paragraph(MARGIN, &entry, TEXT_SIZE, Font::Regular);
+    }
+
+    write(&book.title, front.pages.into_iter().chain(body.pages))
+}
+
+fn recipe_body(layout: &mut Layout, recipe: &Recipe) {
+    let instructions = prose::instructions(recipe);
+    let title = instructions.title.as_deref().unwrap_or("Untitled");
+    layout.paragraph(MARGIN, title, 16.0, Font::Bold);
+    if let Some(preamble) = &instructions.preamble {
+        layout.paragraph(MARGIN, preamble, TEXT_SIZE, Font::Italic);
+    }
+    layout.space(6.0);
+    layout.paragraph(MARGIN, "Ingredients", 12.0, Font::Bold);
+    for ingredient in &instructions.ingredients {
+        layout.paragraph(MARGIN + 12.0, ingredient, TEXT_SIZE, Font::Regular);
+    }
+    layout.space(6.0);
+    layout.paragraph(MARGIN, "Instructions", 12.0, Font::Bold);
+    for (number, step) in instructions.steps.iter().enumerate() {
+        layout.reserve(line_height(TEXT_SIZE));
+        let label = format!("{}.", number + 1);
+        layout.text(MARGIN, &label, TEXT_SIZE, Font::Regular);
+        layout.paragraph(MARGIN + 18.0, step, TEXT_SIZE, Font::Regular);
+    }
+    layout.space(8.0);
+    card(layout, recipe);
+    if let Some(comment) = &instructions.comment {
+        layout.space(6.0);
+        layout.paragraph(MARGIN, comment, TEXT_SIZE, Font::Italic);
+    }
+}
+
+fn card(layout: &mut Layout, recipe: &Recipe) {
+    let cells = html::cells(recipe);
+    let font = |cell: &html::Cell| match cell.derived {
+        true => Font::Italic,
+        false => Font::Regular,
+    };
+    let columns = cells
+        .iter()
+        .map(|c| c.column + c.colspan)
+        .max()
+        .unwrap_or(0);
+    let rows = cells.iter().map(|c| c.row + c.rowspan).max().unwrap_or(0);
+    let mut widths = vec![30.0_f32; columns];
+    for cell in cells.iter().filter(|cell| cell.colspan == 1) {
+        let width = text_width(&cell.text, CARD_SIZE, font(cell)) + 2.0 * PADDING;
+        widths[cell.column] = widths[cell.column].max(width.min(MAX_CELL));
+    }
+    let cell_width =
+        |cell: &html::Cell| -> f32 { widths[cell.column..cell.column + cell.colspan].iter().sum() };
+    let lines: Vec<Vec<String>> = cells
+        .iter()
+        .map(|cell| {
+            let width = cell_width(cell) - 2.0 * PADDING;
+            wrap(&cell.text, width, CARD_SIZE, font(cell))
+        })
+        .collect();
+
+    // Rows are as high as their cells, a cell spanning rows grows the last of them when the rows
+    // are too low for it.
+    let line = line_height(CARD_SIZE);
+    let mut heights = vec![line + 2.0 * PADDING; rows];
+    let mut order: Vec<usize> = (0..cells.len()).collect();
+    order.sort_by_key(|&index| cells[index].rowspan);
+    for index in order {
+        let cell = &cells[index];
+        let needed = lines[index].len() as f32 * line + 2.0 * PADDING;
+        let rows = cell.row..cell.row + cell.rowspan;
+        let height: f32 = heights[rows].iter().sum();
+        if needed > height {
+            heights[cell.row + cell.rowspan - 1] += needed - height;
+        }
+    }
+
+    let width: f32 = widths.iter().sum();
+    let height: f32 = heights.iter().sum();
+    let scale = 1_f32
+        .min((WIDTH - 2.0 * MARGIN) / width)
+        .min((HEIGHT - 2.0 * MARGIN) / height);
+    layout.reserve(height * scale);
+    let top = layout.y;
+    for (cell, lines) in cells.iter().zip(&lines) {
+        let x = MARGIN + scale * widths[..cell.column].iter().sum::<f32>();
+        let y = top - scale * heights[..cell.row].iter().sum::<f32>();
+        let height = scale
+            * heights[cell.row..cell.row + cell.rowspan]
+                .iter()
+                .sum::<f32>();
+        layout.push(Op::Box {
+            x,
+            y: y - height,
+            width: scale * cell_width(cell),
+            height,
+            fill: !cell.headers.is_empty(),
+        });
+        for (n, text) in lines.iter().enumerate() {
+            layout.push(Op::Text {
+                x: x + scale * PADDING,
+                y: y - scale * (PADDING + n as f32 * line + CARD_SIZE),
+                size: scale * CARD_SIZE,
+                font: font(cell),
+                text: text.clone(),
+            });
+        }
+    }
+    layout.y = top - height * scale;
+}
+
+#[derive(Clone, Copy)]
+enum Font {
+    Regular,
+    Bold,
+    Italic,
+}
+
+impl Font {
+    const ALL: [Font; 3] = [Font::Regular, Font::Bold, Font::Italic];
+
+    // Name of the font in the resources of the pages.
+    fn name(self) -> Name<'static> {
+        match self {
+            Font::Regular => Name(b"F1"),
+            Font::Bold => Name(b"F2"),
+            Font::Italic => Name(b"F3"),
+        }
+    }
+
+    fn base_font(self) -> Name<'static> {
+        match self {
+            Font::Regular => Name(b"Helvetica"),
+            Font::Bold => Name(b"Helvetica-Bold"),
+            Font::Italic => Name(b"Helvetica-Oblique"),
+        }
+    }
+}
+
+// Drawing operations, coordinates are from the bottom left of the page.
+enum Op {
+    Text {
+        x: f32,
+        y: f32,
+        size: f32,
+        font: Font,
+        text: String,
+    },
+    Box {
+        x: f32,
+        y: f32,
+        width: f32,
+        height: f32,
+        fill: bool,
+    },
+}
+
+// Pages of drawing operations and the position of the next line on the last one.
+#[derive(Default)]
+struct Layout {
+    pages: Vec<Vec<Op>>,
+    y: f32,
+}
+
+impl Layout {
+    fn new_page(&mut self) {
+        self.pages.push(Vec::new());
+        self.y = HEIGHT - MARGIN;
+    }
+
+    fn page(&self) -> usize {
+        self.pages.len() - 1
+    }
+
+    // Make room for `height` points, starting a new page when they don't fit on this one.
+    fn reserve(&mut self, height: f32) {
+        if self.pages.is_empty() || self.y - height < MARGIN {
+            self.new_page();
+        }
+    }
+
+    fn space(&mut self, height: f32) {
+        self.y -= height;
+    }
+
+    fn push(&mut self, op: Op) {
+        if self.pages.is_empty() {
+            self.new_page();
+        }
+        self.pages.last_mut().expect("there is a page").push(op);
+    }
+
+    // Write text on the next line without moving past it.
+    fn text(&mut self, x: f32, text: &str, size: f32, font: Font) {
+        self.push(Op::Text {
+            x,
+            y: self.y - size,
+            size,
+            font,
+            text: text.to_string(),
+        });
+    }
+
+    // Write the text wrapped between `x` and the right margin.
+    fn paragraph(&mut self, x: f32, text: &str, size: f32, font: Font) {
+        for line in wrap(text, WIDTH - MARGIN - x, size, font) {
+            self.reserve(line_height(size));
+            self.text(x, &line, size, font);
+            self.y -= line_height(size);
+        }
+    }
+
+    // Write a line of the contents with its page number at the right margin.
+    fn entry(&mut self, x: f32, text: &str, page: usize, font: Font) {
+        let size = 11.0;
+        self.reserve(line_height(size));
+        let page = page.to_string();
+        let number_width = text_width(&page, size, font);
+        let mut text = text.to_string();
+        while text_width(&text, size, font) > WIDTH - MARGIN - x - number_width - 12.0 {
+            text.pop();
+        }
+        self.text(x, &text, size, font);
+        self.text(WIDTH - MARGIN - number_width, &page, size, font);
+        self.y -= line_height(size);
+    }
+}
+
+fn line_height(size: f32) -> f32 {
+    size * 1.35
+}
+
+// Break the text into lines at most `width` points wide, a word wider than that is a line of its
+// own.
+fn wrap(text: &str, width: f32, size: f32, font: Font) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    let mut line = String::new();
+    for word in text.split_whitespace() {
+        let candidate = match line.is_empty() {
+            true => word.to_string(),
+            false => format!("{line} {word}"),
+        };
+        if !line.is_empty() && text_width(&candidate, size, font) > width {
+            lines.push(std::mem::replace(&mut line, word.to_string()));
+        } else {
+            line = candidate;
+        }
+    }
+    lines.push(line);
+    lines
+}
+
+// Width of the text in points.
+fn text_width(text: &str, size: f32, font: Font) -> f32 {
+    let width: u32 = text
+        .chars()
+        .map(|c| match c {
+            ' '..='~' => u32::from(HELVETICA[c as usize - ' ' as usize]),
+            _ => 556,
+        })
+        .sum();
+    // Bold glyphs are about this much wider than the regular ones.
+    let bold = match font {
+        Font::Bold => 1.08,
+        _ => 1.0,
+    };
+    width as f32 * size / 1000.0 * bold
+}
+
+// Widths of the printable ASCII characters in Helvetica, in thousandths of the font size.
+#[rustfmt::skip]
+const HELVETICA: [u16; 95] = [
+    278, 278, 355, 556, 556, 889, 667, 191, 333, 333, 389, 584, 278, 333, 278, 278,
+    556, 556, 556, 556, 556, 556, 556, 556, 556, 556, 278, 278, 584, 584, 584, 556,
+    1015, 667, 667, 722, 722, 667, 611, 778, 722, 278, 500, 667, 556, 833, 722, 778,
+    667, 778, 722, 667, 611, 722, 667, 944, 667, 667, 611, 278, 278, 278, 469, 556,
+    333, 556, 556, 500, 556, 556, 278, 556, 556, 222, 222, 500, 222, 833, 556, 556,
+    556, 556, 333, 500, 278, 556, 500, 722, 500, 500, 500, 334, 260, 334, 584,
+];
+
+// Encode the text in Windows-1252, characters it doesn't have become `?`.
+fn encode(text: &str) -> Vec<u8> {
+    text.chars()
+        .map(|c| match c {
+            ' '..='~' | '\u{a0}'..='\u{ff}' => c as u8,
+            '…' => 0x85,
+            '‘' => 0x91,
+            '’' => 0x92,
+            '“' => 0x93,
+            '”' => 0x94,
+            '•' => 0x95,
+            '–' => 0x96,
+            '—' => 0x97,
+            _ => b'?',
+        })
+        .collect()
+}
+
+fn write(title: &str, pages: impl Iterator<Item = Vec<Op>>) -> Vec<u8> {
+    let pages: Vec<Vec<Op>> = pages.collect();
+    let catalog = Ref::new(1);
+    let tree = Ref::new(2);
+    let info = Ref::new(3);
+    let font_ref = |font: Font| Ref::new(4 + font as i32);
+    let page_ref = |page: usize| Ref::new(7 + 2 * page as i32);
+    let content_ref = |page: usize| Ref::new(8 + 2 * page as i32);
+
+    let mut pdf = Pdf::new();
+    pdf.catalog(catalog).pages(tree);
+    pdf.document_info(info).title(TextStr(title));
+    pdf.pages(tree)
+        .kids((0..pages.len()).map(page_ref))
+        .count(pages.len() as i32);
+    for font in Font::ALL {
+        pdf.type1_font(font_ref(font))
+            .base_font(font.base_font())
+            .encoding_predefined(Name(b"WinAnsiEncoding"));
+    }
+    for (number, ops) in pages.iter().enumerate() {
+        let mut page = pdf.page(page_ref(number));
+        page.media_box(Rect::new(0.0, 0.0, WIDTH, HEIGHT))
+            .parent(tree)
+            .contents(content_ref(number));
+        let mut resources = page.resources();
+        let mut fonts = resources.fonts();
+        for font in Font::ALL {
+            fonts.pair(font.name(), font_ref(font));
+        }
+        fonts.finish();
+        resources.finish();
+        page.finish();
+
+        let mut content = Content::new();
+        content.set_line_width(0.5);
+        for op in ops {
+            match op {
+                Op::Text {
+                    x,
+                    y,
+                    size,
+                    font,
+                    text,
+                } => {
+                    content.begin_text();
+                    content.set_font(font.name(), *size);
+                    content.next_line(*x, *y);
+                    content.show(Str(&encode(text)));
+                    content.end_text();
+                }
+                Op::Box {
+                    x,
+                    y,
+                    width,
+                    height,
+                    fill,
+                } => {
+                    content.rect(*x, *y, *width, *height);
+                    if *fill {
+                        content.set_fill_gray(0.93);
+                        content.fill_nonzero_and_stroke();
+                        content.set_fill_gray(0.0);
+                    } else {
+                        content.stroke();
+                    }
+                }
+            }
+        }
+        // Every page but the title page is numbered at the bottom.
+        if number > 0 {
+            let label = (number + 1).to_string();
+            let x = (WIDTH - text_width(&label, TEXT_SIZE, Font::Regular)) / 2.0;
+            content.begin_text();
+            content.set_font(Font::Regular.name(), TEXT_SIZE);
+            content.next_line(x, MARGIN / 2.0);
+            content.show(Str(label.as_bytes()));
+            content.end_text();
+        }
+        pdf.stream(content_ref(number), &content.finish());
+    }
+    pdf.finish()
+}
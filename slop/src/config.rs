@@ -0,0 +1,129 @@
+use std::path::{Path, PathBuf};
+
+use thiserror::Error;
+
+use crate::{
+    diagnostic::Severity,
+    equipment::{Equipment, Vocabulary},
+    lint::LintConfig,
+};
+
+// Project configuration read from a `slop.toml` file.
+//
+// The file is looked up from the directory of a recipe upwards so a single file configures every
+// recipe beneath it, e.g.
+//
+//     [lint]
+//     missing-title = "warning"
+//     duplicate-ingredient = "off"
+//
+//     [equipment]
+//     wok = ["wok", "stir fry"]
+
+/// Name of the configuration file.
+pub const FILE_NAME: &str = "slop.toml";
+
+#[derive(Clone, Debug, Default, PartialEq)]
+#[non_exhaustive]
+pub struct Config {
+    pub lint: LintConfig,
+    pub equipment: Vocabulary,
+}
+
+#[derive(Error, Debug)]
+#[non_exhaustive]
+pub enum Error {
+    #[error("reading {0}: {1}")]
+    Io(PathBuf, std::io::Error),
+    #[error("invalid TOML: {0}")]
+    Toml(#[from] toml::de::Error),
+    #[error("`{0}` must be a table")]
+    NotATable(String),
+    #[error("unknown lint rule `{0}`")]
+    UnknownRule(String),
+    #[error("invalid level for lint rule `{0}`, expected one of \"off\", \"hint\", \"warning\" or \"error\"")]
+    InvalidLevel(String),
+    #[error(
+        "invalid equipment `{0}`, expected a list of words or a table with `words` and `shared`"
+    )]
+    InvalidEquipment(String),
+}
+
+type Result<T> = std::result::Result<T, Error>;
+
+impl Config {
+    pub fn from_toml(src: &str) -> Result<Self> {
+        let table: toml::Table = src.parse()?;
+        let mut config = Config::default();
+        if let Some(lint) = table.get("lint") {
+            let lint = lint
+                .as_table()
+                .ok_or_else(|| Error::NotATable("lint".to_string()))?;
+            let names: Vec<&str> = crate::lint::rules().iter().map(|r| r.name()).collect();
+            for (name, level) in lint {
+                if !names.contains(&name.as_str()) {
+                    return Err(Error::UnknownRule(name.clone()));
+                }
+                let severity = match level.as_str() {
+                    Some("off") => None,
+                    Some("hint") => Some(Severity::Hint),
+                    Some("warning") => Some(Severity::Warning),
+                    Some("error") => Some(Severity::Error),
+                    _ => return Err(Error::InvalidLevel(name.clone())),
+                };
+                config.lint.rules.insert(name.clone(), severity);
+            }
+        }
+        if let Some(equipment) = table.get("equipment") {
+            let equipment = equipment
+                .as_table()
+                .ok_or_else(|| Error::NotATable("equipment".to_string()))?;
+            for (name, value) in equipment {
+                let invalid = || Error::InvalidEquipment(name.clone());
+                let (words, shared) = match value {
+                    toml::Value::Array(words) => (words, false),
+                    toml::Value::Table(table) => (
+                        table
+                            .get("words")
+                            .and_then(toml::Value::as_array)
+                            .ok_or_else(invalid)?,
+                        match table.get("shared") {
+                            Some(shared) => shared.as_bool().ok_or_else(invalid)?,
+                            None => false,
+                        },
+                    ),
+                    _ => return Err(invalid()),
+                };
+                let words = words
+                    .iter()
+                    .map(|word| word.as_str().ok_or_else(invalid))
+                    .collect::<Result<Vec<_>>>()?;
+                config
+                    .equipment
+                    .insert(Equipment::new(name, &words, shared));
+            }
+        }
+        Ok(config)
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        let src = std::fs::read_to_string(path).map_err(|e| Error::Io(path.to_owned(), e))?;
+        Self::from_toml(&src)
+    }
+
+    /// Load the nearest configuration file in `dir` or its ancestors, the default
+    /// configuration is used when there is none.
+    pub fn discover(dir: &Path) -> Result<Self> {
+        match find(dir) {
+            Some(path) => Self::load(&path),
+            None => Ok(Self::default()),
+        }
+    }
+}
+
+/// Find the nearest configuration file in `dir` or its ancestors.
+pub fn find(dir: &Path) -> Option<PathBuf> {
+    dir.ancestors()
+        .map(|dir| dir.join(FILE_NAME))
+        .find(|path| path.is_file())
+}
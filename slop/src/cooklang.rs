@@ -0,0 +1,84 @@
+use crate::{
+    annotation::{annotate, AnnotationKind},
+    prose,
+    semantic::{Ingredient, Recipe},
+};
+
+// Export of recipes as Cooklang. See `import::cooklang` for the reverse direction.
+//
+// Cooklang describes a recipe as a sequence of steps, so the tree is linearised into the
+// numbered sentences of `prose` and each sentence becomes a step. Ingredients are marked up as
+// `@name{quantity%unit}` where they are used and exact durations as `~{10%minutes}` timers.
+// Derived ingredients refer to the result of another step and are left as plain text.
+
+/// Write the recipe as a Cooklang file.
+pub fn to_cooklang(recipe: &Recipe) -> String {
+    let mut out = String::new();
+    if let Some(title) = recipe.title() {
+        out += &format!(">> title: {}\n", one_line(title));
+    }
+    if let Some(preamble) = recipe.preamble() {
+        out += &format!(">> description: {}\n", one_line(preamble));
+    }
+    if let Some(recipe_yield) = recipe.recipe_yield() {
+        out += &format!(">> servings: {recipe_yield}\n");
+    }
+    for step in prose::steps(recipe, markup) {
+        if !out.is_empty() {
+            out += "\n";
+        }
+        out += &format!("{}\n", timers(&step));
+    }
+    match recipe.comment() {
+        Some(comment) if recipe.recipe_yield().is_none() => {
+            out += &format!("\n> {}\n", one_line(comment))
+        }
+        _ => {}
+    }
+    out
+}
+
+fn markup(ingredient: &Ingredient) -> String {
+    let name = ingredient.text();
+    if ingredient.is_derived() {
+        return name.to_string();
+    }
+    match (ingredient.quantity_text(), ingredient.unit()) {
+        (Some(quantity), Some(unit)) => format!("@{name}{{{quantity}%{unit}}}"),
+        (Some(quantity), None) => format!("@{name}{{{quantity}}}"),
+        _ if name.chars().all(|c| c.is_alphanumeric() || c == '-') => format!("@{name}"),
+        _ => format!("@{name}{{}}"),
+    }
+}
+
+// Mark up the exact durations of a sentence as timers, ranges stay as text.
+fn timers(sentence: &str) -> String {
+    let mut out = sentence.to_string();
+    for annotation in annotate(sentence).into_iter().rev() {
+        let AnnotationKind::Duration(duration) = annotation.kind else {
+            continue;
+        };
+        let span = annotation.span;
+        // Quantities of ingredients can look like durations, e.g. `{2%m}`.
+        let inside_braces = sentence[..span.start].matches('{').count()
+            > sentence[..span.start].matches('}').count();
+        if duration.min != duration.max || duration.is_zero() || inside_braces {
+            continue;
+        }
+        let secs = duration.max.as_secs();
+        let (value, unit) = if secs % 3600 == 0 {
+            (secs / 3600, "hour")
+        } else if secs % 60 == 0 {
+            (secs / 60, "minute")
+        } else {
+            (secs, "second")
+        };
+        let plural = if value == 1 { "" } else { "s" };
+        out.replace_range(span, &format!("~{{{value}%{unit}{plural}}}"));
+    }
+    out
+}
+
+fn one_line(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
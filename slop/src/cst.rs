@@ -0,0 +1,649 @@
+use std::{fmt::Display, iter::Peekable, str::CharIndices};
+
+use crate::{
+    ast::{self, Position, Positioned, Quantity},
+    parser::Error,
+};
+
+// Lossless concrete syntax tree.
+//
+// Unlike [`ast::SourceFile`] the concrete syntax tree keeps every byte of the source, including
+// whitespace and the `:` separators. Printing a tree yields exactly the text it was parsed from,
+// which lets tools compute minimal edits against the author's layout instead of reformatting.
+//
+// The tree follows the model of rowan: a single untyped node type tagged with a [`SyntaxKind`].
+// Whitespace (trivia) is attached to the node that consumed the token it follows.
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum SyntaxKind {
+    // Tokens
+    Whitespace,
+    OpenAngle,
+    CloseAngle,
+    Equal,
+    Hash,
+    Colon,
+    Star,
+    Hat,
+    StarStar,
+    HashStar,
+    HashHash,
+    Number,
+    Fraction,
+    Sentence,
+    /// A dangling `*` or `#` at the end of the input, ignored by the parser.
+    Unknown,
+
+    // Nodes
+    SourceFile,
+    Recipe,
+    Title,
+    Preamble,
+    Comment,
+    Ingredient,
+    Unit,
+    UnaryOp,
+    BinaryOp,
+    MissingOperand,
+    UnusedOperands,
+    /// Tokens consumed while recovering from a parse error.
+    Error,
+}
+
+impl SyntaxKind {
+    pub fn is_trivia(self) -> bool {
+        matches!(self, SyntaxKind::Whitespace | SyntaxKind::Unknown)
+    }
+    pub fn is_operand(self) -> bool {
+        matches!(
+            self,
+            SyntaxKind::Ingredient
+                | SyntaxKind::UnaryOp
+                | SyntaxKind::BinaryOp
+                | SyntaxKind::MissingOperand
+                | SyntaxKind::UnusedOperands
+        )
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct SyntaxToken {
+    kind: SyntaxKind,
+    offset: usize,
+    text: String,
+}
+
+impl SyntaxToken {
+    pub fn kind(&self) -> SyntaxKind {
+        self.kind
+    }
+    pub fn text(&self) -> &str {
+        self.text.as_str()
+    }
+    pub fn text_range(&self) -> Position {
+        self.offset..self.offset + self.text.len()
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct SyntaxNode {
+    kind: SyntaxKind,
+    offset: usize,
+    len: usize,
+    children: Vec<SyntaxElement>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum SyntaxElement {
+    Node(SyntaxNode),
+    Token(SyntaxToken),
+}
+
+impl SyntaxElement {
+    pub fn kind(&self) -> SyntaxKind {
+        match self {
+            SyntaxElement::Node(node) => node.kind(),
+            SyntaxElement::Token(token) => token.kind(),
+        }
+    }
+    pub fn text_range(&self) -> Position {
+        match self {
+            SyntaxElement::Node(node) => node.text_range(),
+            SyntaxElement::Token(token) => token.text_range(),
+        }
+    }
+}
+
+impl SyntaxNode {
+    // Create a new node from its children.
+    //
+    // Empty nodes, i.e. missing operands, have no text to derive an offset from so they are
+    // placed at the provided offset instead.
+    fn new(kind: SyntaxKind, offset: usize, children: Vec<SyntaxElement>) -> Self {
+        let offset = children
+            .iter()
+            .map(|child| child.text_range())
+            .find(|range| !range.is_empty())
+            .map(|range| range.start)
+            .unwrap_or(offset);
+        let len = children.iter().map(|child| child.text_range().len()).sum();
+        Self {
+            kind,
+            offset,
+            len,
+            children,
+        }
+    }
+    pub fn kind(&self) -> SyntaxKind {
+        self.kind
+    }
+    pub fn text_range(&self) -> Position {
+        self.offset..self.offset + self.len
+    }
+    pub fn children(&self) -> impl Iterator<Item = &SyntaxElement> {
+        self.children.iter()
+    }
+    pub fn child_nodes(&self) -> impl Iterator<Item = &SyntaxNode> {
+        self.children.iter().filter_map(|child| match child {
+            SyntaxElement::Node(node) => Some(node),
+            SyntaxElement::Token(_) => None,
+        })
+    }
+    pub fn child_tokens(&self) -> impl Iterator<Item = &SyntaxToken> {
+        self.children.iter().filter_map(|child| match child {
+            SyntaxElement::Node(_) => None,
+            SyntaxElement::Token(token) => Some(token),
+        })
+    }
+    pub fn child_node(&self, kind: SyntaxKind) -> Option<&SyntaxNode> {
+        self.child_nodes().find(|node| node.kind == kind)
+    }
+    pub fn child_token(&self, kind: SyntaxKind) -> Option<&SyntaxToken> {
+        self.child_tokens().find(|token| token.kind == kind)
+    }
+    /// All nodes in the subtree in preorder, including this node.
+    pub fn descendants(&self) -> Vec<&SyntaxNode> {
+        let mut nodes = vec![self];
+        for child in self.child_nodes() {
+            nodes.extend(child.descendants());
+        }
+        nodes
+    }
+    /// All tokens in the subtree in source order.
+    pub fn descendant_tokens(&self) -> Vec<&SyntaxToken> {
+        let mut tokens = Vec::new();
+        for child in &self.children {
+            match child {
+                SyntaxElement::Node(node) => tokens.extend(node.descendant_tokens()),
+                SyntaxElement::Token(token) => tokens.push(token),
+            }
+        }
+        tokens
+    }
+    /// The smallest node that completely covers the range.
+    pub fn covering_node(&self, range: Position) -> &SyntaxNode {
+        for child in self.child_nodes() {
+            let child_range = child.text_range();
+            if child_range.start <= range.start && range.end <= child_range.end {
+                return child.covering_node(range);
+            }
+        }
+        self
+    }
+}
+
+impl Display for SyntaxNode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for token in self.descendant_tokens() {
+            write!(f, "{}", token.text)?;
+        }
+        Ok(())
+    }
+}
+
+// Lexer that mirrors the tokenization of the parser but keeps whitespace.
+//
+// Sentences are split into the trimmed sentence and its trailing whitespace so that the
+// sentence token text is exactly the text used by the AST.
+struct Lexer<'input> {
+    input: &'input str,
+    iter: Peekable<CharIndices<'input>>,
+}
+
+impl<'input> Lexer<'input> {
+    fn new(input: &'input str) -> Self {
+        Self {
+            input,
+            iter: input.char_indices().peekable(),
+        }
+    }
+    fn offset(&mut self) -> usize {
+        self.iter
+            .peek()
+            .map(|(offset, _)| *offset)
+            .unwrap_or(self.input.len())
+    }
+    fn token(&self, kind: SyntaxKind, range: Position) -> SyntaxToken {
+        SyntaxToken {
+            kind,
+            offset: range.start,
+            text: self.input[range].to_string(),
+        }
+    }
+    fn eat_while(&mut self, f: impl Fn(char) -> bool) -> usize {
+        while let Some((_, ch)) = self.iter.peek() {
+            if f(*ch) {
+                self.iter.next();
+            } else {
+                break;
+            }
+        }
+        self.offset()
+    }
+    fn lex(mut self) -> Vec<SyntaxToken> {
+        let mut tokens = Vec::new();
+        while let Some((start, ch)) = self.iter.next() {
+            let kind = match ch {
+                '<' => SyntaxKind::OpenAngle,
+                '>' => SyntaxKind::CloseAngle,
+                '=' => SyntaxKind::Equal,
+                ':' => SyntaxKind::Colon,
+                '^' => SyntaxKind::Hat,
+                '#' => match self.iter.peek() {
+                    Some((_, '*')) => {
+                        self.iter.next();
+                        SyntaxKind::HashStar
+                    }
+                    Some((_, '#')) => {
+                        self.iter.next();
+                        SyntaxKind::HashHash
+                    }
+                    Some(_) => SyntaxKind::Hash,
+                    None => SyntaxKind::Unknown,
+                },
+                '*' => match self.iter.peek() {
+                    Some((_, '*')) => {
+                        self.iter.next();
+                        SyntaxKind::StarStar
+                    }
+                    Some(_) => SyntaxKind::Star,
+                    None => SyntaxKind::Unknown,
+                },
+                ch if ch.is_numeric() => self.lex_number_or_fraction(),
+                ch if ch.is_whitespace() => {
+                    self.eat_while(char::is_whitespace);
+                    SyntaxKind::Whitespace
+                }
+                _ => {
+                    let end = self.eat_while(is_sentence_char);
+                    if end == self.input.len() {
+                        // The parser does not trim a sentence that ends the input.
+                        SyntaxKind::Sentence
+                    } else {
+                        let trimmed = start + self.input[start..end].trim_end().len();
+                        tokens.push(self.token(SyntaxKind::Sentence, start..trimmed));
+                        if trimmed != end {
+                            tokens.push(self.token(SyntaxKind::Whitespace, trimmed..end));
+                        }
+                        continue;
+                    }
+                }
+            };
+            let end = self.offset();
+            tokens.push(self.token(kind, start..end));
+        }
+        tokens
+    }
+    fn lex_number_or_fraction(&mut self) -> SyntaxKind {
+        while let Some((_, ch)) = self.iter.peek() {
+            match ch {
+                '/' => {
+                    self.iter.next();
+                    self.eat_while(char::is_numeric);
+                    return SyntaxKind::Fraction;
+                }
+                '.' => {
+                    self.iter.next();
+                    self.eat_while(char::is_numeric);
+                    return SyntaxKind::Number;
+                }
+                n if n.is_numeric() => {
+                    self.iter.next();
+                }
+                _ => break,
+            }
+        }
+        SyntaxKind::Number
+    }
+}
+
+fn is_sentence_char(ch: char) -> bool {
+    ch != '*' && ch != '#' && ch != '=' && ch != '>' && ch != ':'
+}
+
+/// Parse source into a lossless concrete syntax tree.
+///
+/// The tree always covers the complete input, printing the returned node reproduces `input`.
+pub fn parse(input: &str) -> (SyntaxNode, Vec<Error>) {
+    let mut parser = Parser {
+        tokens: Lexer::new(input).lex(),
+        cursor: 0,
+        len: input.len(),
+        stack: Default::default(),
+        errors: Default::default(),
+    };
+    let src = parser.parse_source_file();
+    (src, parser.errors)
+}
+
+// Same greedy stack parser as [`crate::parser`] but producing syntax nodes.
+struct Parser {
+    tokens: Vec<SyntaxToken>,
+    cursor: usize,
+    len: usize,
+    stack: Vec<SyntaxNode>,
+    errors: Vec<Error>,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<SyntaxKind> {
+        self.tokens.get(self.cursor).map(|token| token.kind)
+    }
+    fn offset(&self) -> usize {
+        self.tokens
+            .get(self.cursor)
+            .map(|token| token.offset)
+            .unwrap_or(self.len)
+    }
+    fn trivia(&mut self) -> Vec<SyntaxElement> {
+        let mut elements = Vec::new();
+        while let Some(token) = self.tokens.get(self.cursor) {
+            if !token.kind.is_trivia() {
+                break;
+            }
+            elements.push(SyntaxElement::Token(token.clone()));
+            self.cursor += 1;
+        }
+        elements
+    }
+    // Consume the next token along with its trailing trivia.
+    fn bump(&mut self) -> Vec<SyntaxElement> {
+        let mut elements = Vec::new();
+        if let Some(token) = self.tokens.get(self.cursor) {
+            elements.push(SyntaxElement::Token(token.clone()));
+            self.cursor += 1;
+        }
+        elements.extend(self.trivia());
+        elements
+    }
+    fn unexpected(&mut self) -> Vec<SyntaxElement> {
+        match self.tokens.get(self.cursor) {
+            Some(token) => {
+                self.errors.push(Error::UnexpectedToken(
+                    token.text.clone(),
+                    token.text_range(),
+                ));
+                let offset = token.offset;
+                let children = self.bump();
+                vec![SyntaxElement::Node(SyntaxNode::new(
+                    SyntaxKind::Error,
+                    offset,
+                    children,
+                ))]
+            }
+            None => {
+                self.errors.push(Error::UnexpectedEOF(self.len..self.len));
+                Vec::new()
+            }
+        }
+    }
+    fn expect_text(&mut self) -> Vec<SyntaxElement> {
+        match self.peek() {
+            Some(SyntaxKind::Sentence) => self.bump(),
+            _ => self.unexpected(),
+        }
+    }
+    fn parse_source_file(&mut self) -> SyntaxNode {
+        let mut children = self.trivia();
+        while let Some(SyntaxKind::OpenAngle) = self.peek() {
+            children.push(SyntaxElement::Node(self.parse_recipe()));
+        }
+        if self.cursor < self.tokens.len() {
+            // The parser ignores anything after the last recipe, keep it so the tree is lossless.
+            let offset = self.offset();
+            let rest = self.tokens[self.cursor..]
+                .iter()
+                .cloned()
+                .map(SyntaxElement::Token)
+                .collect();
+            self.cursor = self.tokens.len();
+            children.push(SyntaxElement::Node(SyntaxNode::new(
+                SyntaxKind::Error,
+                offset,
+                rest,
+            )));
+        }
+        SyntaxNode::new(SyntaxKind::SourceFile, 0, children)
+    }
+    fn parse_recipe(&mut self) -> SyntaxNode {
+        let start = self.offset();
+        let mut children = match self.peek() {
+            Some(SyntaxKind::OpenAngle) => self.bump(),
+            _ => self.unexpected(),
+        };
+        if let Some(title) = self.parse_meta(SyntaxKind::StarStar, SyntaxKind::Title) {
+            children.push(title);
+        }
+        if let Some(preamble) = self.parse_meta(SyntaxKind::HashHash, SyntaxKind::Preamble) {
+            children.push(preamble);
+        }
+        self.parse_operands();
+        let root = match self.stack.len() {
+            1 => self.stack.pop().unwrap(),
+            l if l > 1 => {
+                let offset = self.stack[0].offset;
+                let operands = self.stack.drain(..).map(SyntaxElement::Node).collect();
+                SyntaxNode::new(SyntaxKind::UnusedOperands, offset, operands)
+            }
+            _ => SyntaxNode::new(SyntaxKind::MissingOperand, self.offset(), Vec::new()),
+        };
+        debug_assert!(self.stack.is_empty());
+        children.push(SyntaxElement::Node(root));
+        if let Some(comment) = self.parse_meta(SyntaxKind::HashStar, SyntaxKind::Comment) {
+            children.push(comment);
+        }
+        match self.peek() {
+            Some(SyntaxKind::CloseAngle) => children.extend(self.bump()),
+            _ => children.extend(self.unexpected()),
+        };
+        SyntaxNode::new(SyntaxKind::Recipe, start, children)
+    }
+    // Parse a title, preamble or comment, these are a marker token followed by a sentence.
+    fn parse_meta(&mut self, marker: SyntaxKind, kind: SyntaxKind) -> Option<SyntaxElement> {
+        if self.peek() != Some(marker) {
+            return None;
+        }
+        let offset = self.offset();
+        let mut children = self.bump();
+        children.extend(self.expect_text());
+        Some(SyntaxElement::Node(SyntaxNode::new(kind, offset, children)))
+    }
+    fn parse_operands(&mut self) {
+        while let Some(SyntaxKind::Star) | Some(SyntaxKind::Equal) | Some(SyntaxKind::Hash) =
+            self.peek()
+        {
+            self.parse_operand()
+        }
+    }
+    fn missing(&self, offset: usize) -> SyntaxNode {
+        SyntaxNode::new(SyntaxKind::MissingOperand, offset, Vec::new())
+    }
+    fn parse_operand(&mut self) {
+        let start = self.offset();
+        match self.peek() {
+            Some(SyntaxKind::Star) => {
+                let mut children = self.bump();
+                if let Some(SyntaxKind::Hat) = self.peek() {
+                    children.extend(self.bump());
+                }
+                let mut has_quantities = false;
+                while let Some(SyntaxKind::Number) | Some(SyntaxKind::Fraction) = self.peek() {
+                    children.extend(self.bump());
+                    has_quantities = true;
+                }
+                if has_quantities {
+                    if let Some(SyntaxKind::Sentence) = self.peek() {
+                        let offset = self.offset();
+                        let unit = self.bump();
+                        children.push(SyntaxElement::Node(SyntaxNode::new(
+                            SyntaxKind::Unit,
+                            offset,
+                            unit,
+                        )));
+                    }
+                    match self.peek() {
+                        Some(SyntaxKind::Colon) => children.extend(self.bump()),
+                        _ => children.extend(self.unexpected()),
+                    }
+                }
+                children.extend(self.expect_text());
+                self.stack
+                    .push(SyntaxNode::new(SyntaxKind::Ingredient, start, children));
+            }
+            Some(SyntaxKind::Equal) => {
+                let operand = self.stack.pop().unwrap_or(self.missing(start));
+                let mut children = vec![SyntaxElement::Node(operand)];
+                children.extend(self.bump());
+                children.extend(self.expect_text());
+                self.stack
+                    .push(SyntaxNode::new(SyntaxKind::UnaryOp, start, children));
+            }
+            Some(SyntaxKind::Hash) => {
+                let second = self.stack.pop().unwrap_or(self.missing(start));
+                let first = self.stack.pop().unwrap_or(self.missing(start));
+                let mut children = vec![SyntaxElement::Node(first), SyntaxElement::Node(second)];
+                children.extend(self.bump());
+                children.extend(self.expect_text());
+                self.stack
+                    .push(SyntaxNode::new(SyntaxKind::BinaryOp, start, children));
+            }
+            _ => {
+                self.unexpected();
+            }
+        }
+    }
+}
+
+/// Convert a concrete syntax tree into the abstract syntax tree.
+///
+/// For well formed source the result is identical to [`crate::parse`], positions of nodes
+/// recovered from errors are derived from the concrete tree instead.
+pub fn convert_source_file(node: &SyntaxNode) -> ast::SourceFile {
+    ast::SourceFile {
+        recipes: node
+            .child_nodes()
+            .filter(|node| node.kind == SyntaxKind::Recipe)
+            .map(convert_recipe)
+            .collect(),
+    }
+}
+
+fn convert_recipe(node: &SyntaxNode) -> ast::Recipe {
+    let end = node
+        .child_token(SyntaxKind::CloseAngle)
+        .map(|token| token.offset)
+        .unwrap_or(node.text_range().end);
+    let meta = |kind| {
+        node.child_node(kind)
+            .and_then(|meta| meta.child_token(SyntaxKind::Sentence))
+            .map(|sentence| sentence.text.clone())
+    };
+    ast::Recipe {
+        position: node.offset..end,
+        title: meta(SyntaxKind::Title),
+        preamble: meta(SyntaxKind::Preamble),
+        comment: meta(SyntaxKind::Comment),
+        root: node
+            .child_nodes()
+            .find(|child| child.kind.is_operand())
+            .map(convert_operand)
+            .unwrap_or(ast::Operand::MissingOperand {
+                position: Default::default(),
+            }),
+    }
+}
+
+fn convert_operand(node: &SyntaxNode) -> ast::Operand {
+    let text = || {
+        node.child_token(SyntaxKind::Sentence)
+            .map(|sentence| sentence.text.clone())
+            .unwrap_or_default()
+    };
+    // Operator positions start at the operator token and include the trailing whitespace of the
+    // operator text.
+    let position = |marker| {
+        let start = node
+            .child_token(marker)
+            .map(|token| token.offset)
+            .unwrap_or(node.offset);
+        start..node.text_range().end
+    };
+    let mut operands = node
+        .child_nodes()
+        .filter(|child| child.kind.is_operand())
+        .map(convert_operand);
+    match node.kind {
+        SyntaxKind::Ingredient => ast::Operand::Ingredient {
+            position: position(SyntaxKind::Star),
+            derived: node.child_token(SyntaxKind::Hat).is_some(),
+            quantities: node
+                .child_tokens()
+                .filter_map(|token| match token.kind {
+                    SyntaxKind::Number => Some(Quantity::Number(token.text.clone())),
+                    SyntaxKind::Fraction => Some(Quantity::Fraction(token.text.clone())),
+                    _ => None,
+                })
+                .collect(),
+            unit: node
+                .child_node(SyntaxKind::Unit)
+                .and_then(|unit| unit.child_token(SyntaxKind::Sentence))
+                .map(|unit| unit.text.clone()),
+            text: text(),
+        },
+        SyntaxKind::UnaryOp => ast::Operand::UnaryOp {
+            position: position(SyntaxKind::Equal),
+            operand: Box::new(
+                operands
+                    .next()
+                    .expect("unary operator should have an operand"),
+            ),
+            text: text(),
+        },
+        SyntaxKind::BinaryOp => ast::Operand::BinaryOp {
+            position: position(SyntaxKind::Hash),
+            first: Box::new(
+                operands
+                    .next()
+                    .expect("binary operator should have operands"),
+            ),
+            second: Box::new(
+                operands
+                    .next()
+                    .expect("binary operator should have operands"),
+            ),
+            text: text(),
+        },
+        SyntaxKind::UnusedOperands => {
+            let operands: Vec<ast::Operand> = operands.collect();
+            let start = operands[0].position().start;
+            let end = operands.iter().last().unwrap().position().end;
+            ast::Operand::UnusedOperands {
+                position: start..end,
+                operands,
+            }
+        }
+        _ => ast::Operand::MissingOperand {
+            position: node.offset..node.offset,
+        },
+    }
+}
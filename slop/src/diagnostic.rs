@@ -0,0 +1,168 @@
+use std::fmt::Display;
+
+use crate::ast::Position;
+
+// Diagnostics reported about slop source.
+//
+// Every diagnostic has a stable code so tools can refer to a specific problem, e.g. to
+// suppress it or to look up its documentation. Codes are never reused once published.
+
+/// Stable identifier of a kind of diagnostic, e.g. `S0001`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Code(&'static str);
+
+impl Code {
+    pub const fn new(code: &'static str) -> Self {
+        Self(code)
+    }
+    pub fn as_str(&self) -> &'static str {
+        self.0
+    }
+}
+
+impl Display for Code {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// Codes of the diagnostics produced by parsing and compiling.
+pub mod codes {
+    use super::Code;
+
+    pub const UNEXPECTED_TOKEN: Code = Code::new("S0001");
+    pub const UNEXPECTED_EOF: Code = Code::new("S0002");
+    pub const MISSING_OPERAND: Code = Code::new("S0003");
+    pub const UNUSED_OPERANDS: Code = Code::new("S0004");
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum Severity {
+    Hint,
+    Warning,
+    Error,
+}
+
+impl Display for Severity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Severity::Hint => write!(f, "hint"),
+            Severity::Warning => write!(f, "warning"),
+            Severity::Error => write!(f, "error"),
+        }
+    }
+}
+
+/// A span of source with a message describing its role in a diagnostic.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Label {
+    pub span: Position,
+    pub message: String,
+}
+
+/// Replace the text of a span of the original source.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct TextEdit {
+    pub span: Position,
+    pub text: String,
+}
+
+/// A suggested change to the source that resolves a diagnostic.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Fix {
+    pub message: String,
+    /// Edits against the original source, they never overlap.
+    pub edits: Vec<TextEdit>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[non_exhaustive]
+pub struct Diagnostic {
+    pub code: Code,
+    pub severity: Severity,
+    pub message: String,
+    /// Where the problem is.
+    pub primary: Label,
+    /// Other spans related to the problem.
+    pub labels: Vec<Label>,
+    pub notes: Vec<String>,
+    pub help: Vec<String>,
+    pub fixes: Vec<Fix>,
+}
+
+impl Diagnostic {
+    pub fn new(code: Code, severity: Severity, message: impl Into<String>, span: Position) -> Self {
+        Self {
+            code,
+            severity,
+            message: message.into(),
+            primary: Label {
+                span,
+                message: String::new(),
+            },
+            labels: Vec::new(),
+            notes: Vec::new(),
+            help: Vec::new(),
+            fixes: Vec::new(),
+        }
+    }
+    pub fn error(code: Code, message: impl Into<String>, span: Position) -> Self {
+        Self::new(code, Severity::Error, message, span)
+    }
+    pub fn warning(code: Code, message: impl Into<String>, span: Position) -> Self {
+        Self::new(code, Severity::Warning, message, span)
+    }
+    pub fn hint(code: Code, message: impl Into<String>, span: Position) -> Self {
+        Self::new(code, Severity::Hint, message, span)
+    }
+    pub fn with_primary_message(mut self, message: impl Into<String>) -> Self {
+        self.primary.message = message.into();
+        self
+    }
+    pub fn with_label(mut self, span: Position, message: impl Into<String>) -> Self {
+        self.labels.push(Label {
+            span,
+            message: message.into(),
+        });
+        self
+    }
+    pub fn with_note(mut self, note: impl Into<String>) -> Self {
+        self.notes.push(note.into());
+        self
+    }
+    pub fn with_help(mut self, help: impl Into<String>) -> Self {
+        self.help.push(help.into());
+        self
+    }
+    pub fn with_fix(mut self, fix: Fix) -> Self {
+        self.fixes.push(fix);
+        self
+    }
+    pub fn span(&self) -> &Position {
+        &self.primary.span
+    }
+}
+
+impl Display for Diagnostic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}[{}]: {} at {}..{}",
+            self.severity, self.code, self.message, self.primary.span.start, self.primary.span.end
+        )?;
+        for note in &self.notes {
+            write!(f, "\n  note: {note}")?;
+        }
+        for help in &self.help {
+            write!(f, "\n  help: {help}")?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,467 @@
+use thiserror::Error;
+
+use crate::{
+    annotation::annotate,
+    ast::{self, Quantity},
+    format,
+    semantic::{Ingredient, Operand, Recipe, SourceFile},
+};
+
+// Programmatic editing of semantic recipe trees.
+//
+// Operands are addressed by a path of indexes into the operands of each operator starting from
+// the root of a recipe, the empty path is the root itself. Edits keep the tree in the same shape
+// the compiler produces, i.e. `+` operators are merged into their parent, so that regenerated
+// source compiles back to the edited tree.
+
+/// Create a new ingredient, the quantity is parsed the same as in slop source, e.g. `1 1/2`.
+pub fn ingredient(
+    quantity: Option<&str>,
+    unit: Option<&str>,
+    text: &str,
+) -> Result<Ingredient, Error> {
+    new_ingredient(false, quantity, unit, text)
+}
+
+/// Create a new derived `^` ingredient, the by product of an earlier step, see [`ingredient`].
+pub fn derived_ingredient(
+    quantity: Option<&str>,
+    unit: Option<&str>,
+    text: &str,
+) -> Result<Ingredient, Error> {
+    new_ingredient(true, quantity, unit, text)
+}
+
+fn new_ingredient(
+    derived: bool,
+    quantity: Option<&str>,
+    unit: Option<&str>,
+    text: &str,
+) -> Result<Ingredient, Error> {
+    let quantities = match quantity {
+        Some(quantity) => {
+            let value = parse_quantities(quantity)?
+                .iter()
+                .map(f64::try_from)
+                .sum::<Result<f64, _>>()
+                .map_err(|_| Error::InvalidQuantity(quantity.to_string()))?;
+            Some((quantity.to_string(), value))
+        }
+        None => None,
+    };
+    Ok(Ingredient {
+        position: Default::default(),
+        derived,
+        quantities,
+        unit: unit.map(str::to_string),
+        text: text.to_string(),
+    })
+}
+
+/// Create a new operator over the operands.
+pub fn operator(text: &str, operands: Vec<Operand>) -> Operand {
+    let mut op = Operand::Operator {
+        position: Default::default(),
+        text: text.to_string(),
+        operands,
+        annotations: annotate(text),
+    };
+    flatten(&mut op);
+    op
+}
+
+/// Create a new recipe with the operand at `root` as its tree.
+pub fn recipe(
+    title: Option<&str>,
+    preamble: Option<&str>,
+    comment: Option<&str>,
+    root: Operand,
+) -> Recipe {
+    Recipe {
+        position: Default::default(),
+        title: title.map(str::to_string),
+        preamble: preamble.map(str::to_string),
+        comment: comment.map(str::to_string),
+        root,
+    }
+}
+
+/// Create a new file of the recipes, e.g. to generate its source with [`to_source`].
+pub fn source_file(recipes: Vec<Recipe>) -> SourceFile {
+    SourceFile { recipes }
+}
+
+pub fn get<'a>(root: &'a Operand, path: &[usize]) -> Option<&'a Operand> {
+    match path.split_first() {
+        None => Some(root),
+        Some((index, rest)) => operands(root)?.get(*index).and_then(|op| get(op, rest)),
+    }
+}
+
+pub fn get_mut<'a>(root: &'a mut Operand, path: &[usize]) -> Option<&'a mut Operand> {
+    match path.split_first() {
+        None => Some(root),
+        Some((index, rest)) => operands_mut(root)?
+            .get_mut(*index)
+            .and_then(|op| get_mut(op, rest)),
+    }
+}
+
+/// Insert an operand as the `index` operand of the operator at `parent`.
+pub fn insert(
+    root: &mut Operand,
+    parent: &[usize],
+    index: usize,
+    operand: Operand,
+) -> Result<(), Error> {
+    let parent_op = get_mut(root, parent).ok_or_else(|| Error::InvalidPath(parent.to_vec()))?;
+    let ops = operands_mut(parent_op).ok_or_else(|| Error::NotAnOperator(parent.to_vec()))?;
+    if index > ops.len() {
+        return Err(Error::InvalidPath([parent, &[index]].concat()));
+    }
+    ops.insert(index, operand);
+    flatten(parent_op);
+    Ok(())
+}
+
+/// Insert a new ingredient as the `index` operand of the operator at `parent`.
+pub fn insert_ingredient(
+    root: &mut Operand,
+    parent: &[usize],
+    index: usize,
+    ingredient: Ingredient,
+) -> Result<(), Error> {
+    insert(root, parent, index, Operand::Ingredient(ingredient))
+}
+
+/// Replace the operand at `path` with a unary operator applied to it.
+pub fn wrap_unary(root: &mut Operand, path: &[usize], text: &str) -> Result<(), Error> {
+    wrap(root, path, text, None)
+}
+
+/// Replace the operand at `path` with a binary operator applied to it and `second`.
+pub fn wrap_binary(
+    root: &mut Operand,
+    path: &[usize],
+    second: Operand,
+    text: &str,
+) -> Result<(), Error> {
+    wrap(root, path, text, Some(second))
+}
+
+fn wrap(
+    root: &mut Operand,
+    path: &[usize],
+    text: &str,
+    second: Option<Operand>,
+) -> Result<(), Error> {
+    let target = get_mut(root, path).ok_or_else(|| Error::InvalidPath(path.to_vec()))?;
+    let first = std::mem::replace(
+        target,
+        Operand::MissingOperand {
+            position: Default::default(),
+        },
+    );
+    *target = operator(text, [Some(first), second].into_iter().flatten().collect());
+    if let Some((_, parent)) = path.split_last() {
+        flatten(get_mut(root, parent).expect("parent of a valid path should exist"));
+    }
+    Ok(())
+}
+
+/// Replace the operand at `path`, returning the previous operand.
+pub fn replace(root: &mut Operand, path: &[usize], operand: Operand) -> Result<Operand, Error> {
+    let target = get_mut(root, path).ok_or_else(|| Error::InvalidPath(path.to_vec()))?;
+    let old = std::mem::replace(target, operand);
+    if let Some((_, parent)) = path.split_last() {
+        flatten(get_mut(root, parent).expect("parent of a valid path should exist"));
+    }
+    Ok(old)
+}
+
+/// Delete the operand at `path`, returning it.
+///
+/// Deleting an operand of a binary operator makes it a unary operator of the other operand, e.g.
+/// deleting the eggs of `*flour *eggs #whisk` leaves `*flour =whisk`. Deleting the root or the
+/// only operand of an operator leaves a missing operand in its place, as no operator is without
+/// operands. Deleting one of two unused operands leaves the other as the root.
+pub fn delete(root: &mut Operand, path: &[usize]) -> Result<Operand, Error> {
+    match path.split_last() {
+        None => Ok(std::mem::replace(
+            root,
+            Operand::MissingOperand {
+                position: Default::default(),
+            },
+        )),
+        Some((index, parent)) => {
+            let parent = get_mut(root, parent).ok_or_else(|| Error::InvalidPath(path.to_vec()))?;
+            let ops = operands_mut(parent).ok_or_else(|| Error::InvalidPath(path.to_vec()))?;
+            let deleted = match ops.len() {
+                len if *index >= len => return Err(Error::InvalidPath(path.to_vec())),
+                1 => std::mem::replace(
+                    &mut ops[0],
+                    Operand::MissingOperand {
+                        position: Default::default(),
+                    },
+                ),
+                _ => ops.remove(*index),
+            };
+            // A single unused operand is just the root of the recipe.
+            if let Operand::UnusedOperands { operands, .. } = parent {
+                if operands.len() == 1 {
+                    *parent = operands.remove(0);
+                }
+            }
+            Ok(deleted)
+        }
+    }
+}
+
+/// Move the operand at `from` to be the `index` operand of the operator at `to`.
+///
+/// The `to` path is relative to the tree before the move.
+pub fn move_subtree(
+    root: &mut Operand,
+    from: &[usize],
+    to: &[usize],
+    index: usize,
+) -> Result<(), Error> {
+    if from.is_empty() || to.starts_with(from) {
+        return Err(Error::MoveIntoSelf);
+    }
+    if get(root, to).and_then(operands).is_none() {
+        return Err(Error::NotAnOperator(to.to_vec()));
+    }
+    let operand = delete(root, from)?;
+    // Removing the operand shifts its later siblings, adjust the destination to match.
+    let mut to = to.to_vec();
+    let (from_index, from_parent) = from.split_last().unwrap();
+    if to.len() > from_parent.len() && to.starts_with(from_parent) {
+        let sibling = &mut to[from_parent.len()];
+        if *sibling > *from_index {
+            *sibling -= 1;
+        }
+    }
+    let index = if to == from_parent && index > *from_index {
+        index - 1
+    } else {
+        index
+    };
+    insert(root, &to, index, operand)
+}
+
+fn operands(op: &Operand) -> Option<&Vec<Operand>> {
+    match op {
+        Operand::Operator { operands, .. } | Operand::UnusedOperands { operands, .. } => {
+            Some(operands)
+        }
+        Operand::Ingredient(_) | Operand::MissingOperand { .. } => None,
+    }
+}
+
+fn operands_mut(op: &mut Operand) -> Option<&mut Vec<Operand>> {
+    match op {
+        Operand::Operator { operands, .. } | Operand::UnusedOperands { operands, .. } => {
+            Some(operands)
+        }
+        Operand::Ingredient(_) | Operand::MissingOperand { .. } => None,
+    }
+}
+
+// Merge the operands of `+` operators into their parent operator, this is the same shape
+// the compiler produces for binary operators. Unary operators are left as is.
+fn flatten(op: &mut Operand) {
+    if let Operand::Operator { operands, .. } = op {
+        if operands.len() < 2 {
+            return;
+        }
+        let mut flat = Vec::with_capacity(operands.len());
+        for operand in operands.drain(..) {
+            match operand {
+                Operand::Operator { text, operands, .. } if text == "+" => flat.extend(operands),
+                operand => flat.push(operand),
+            }
+        }
+        *operands = flat;
+    }
+}
+
+/// Generate formatted slop source for the file.
+///
+/// Compiling the returned source produces the same tree, apart from positions.
+pub fn to_source(file: &SourceFile) -> Result<String, Error> {
+    Ok(format::format(&to_ast(file)?))
+}
+
+/// Convert a semantic tree back into an abstract syntax tree.
+pub fn to_ast(file: &SourceFile) -> Result<ast::SourceFile, Error> {
+    Ok(ast::SourceFile {
+        recipes: file
+            .recipes
+            .iter()
+            .map(recipe_to_ast)
+            .collect::<Result<_, _>>()?,
+    })
+}
+
+fn recipe_to_ast(r: &Recipe) -> Result<ast::Recipe, Error> {
+    for text in [&r.title, &r.preamble].into_iter().flatten() {
+        check_text(text)?;
+    }
+    // The comment is last in the recipe so it may span lines.
+    if let Some(comment) = &r.comment {
+        check_sentence(comment)?;
+    }
+    Ok(ast::Recipe {
+        position: r.position.clone(),
+        title: r.title.clone(),
+        preamble: r.preamble.clone(),
+        comment: r.comment.clone(),
+        root: operand_to_ast(&r.root)?,
+    })
+}
+
+fn operand_to_ast(op: &Operand) -> Result<ast::Operand, Error> {
+    match op {
+        Operand::Ingredient(ing) => {
+            check_text(&ing.text)?;
+            let quantities = match &ing.quantities {
+                Some((quantity, _)) => parse_quantities(quantity)?,
+                None => Vec::new(),
+            };
+            match &ing.unit {
+                Some(_) if quantities.is_empty() => {
+                    return Err(Error::UnitWithoutQuantity(ing.text.clone()))
+                }
+                Some(unit) => check_text(unit)?,
+                None => {}
+            }
+            Ok(ast::Operand::Ingredient {
+                position: ing.position.clone(),
+                derived: ing.derived,
+                quantities,
+                unit: ing.unit.clone(),
+                text: ing.text.clone(),
+            })
+        }
+        Operand::Operator {
+            position,
+            text,
+            operands,
+            ..
+        } => {
+            check_text(text)?;
+            if operands.len() > 1
+                && operands
+                    .iter()
+                    .any(|op| matches!(op, Operand::Operator { text, .. } if text == "+"))
+            {
+                return Err(Error::NestedCombine);
+            }
+            let mut operands = operands.iter();
+            let first = operands
+                .next()
+                .ok_or_else(|| Error::EmptyOperator(text.clone()))?;
+            let mut acc = operand_to_ast(first)?;
+            let rest: Vec<&Operand> = operands.collect();
+            if rest.is_empty() {
+                return Ok(ast::Operand::UnaryOp {
+                    position: position.clone(),
+                    operand: Box::new(acc),
+                    text: text.clone(),
+                });
+            }
+            // Combine all but the last operand with `+`, the compiler merges them back into
+            // a single operator.
+            for (i, operand) in rest.iter().enumerate() {
+                let last = i == rest.len() - 1;
+                acc = ast::Operand::BinaryOp {
+                    position: if last {
+                        position.clone()
+                    } else {
+                        Default::default()
+                    },
+                    first: Box::new(acc),
+                    second: Box::new(operand_to_ast(operand)?),
+                    text: if last { text.clone() } else { "+".to_string() },
+                };
+            }
+            Ok(acc)
+        }
+        Operand::MissingOperand { position } => Ok(ast::Operand::MissingOperand {
+            position: position.clone(),
+        }),
+        Operand::UnusedOperands { position, operands } => Ok(ast::Operand::UnusedOperands {
+            position: position.clone(),
+            operands: operands
+                .iter()
+                .map(operand_to_ast)
+                .collect::<Result<_, _>>()?,
+        }),
+    }
+}
+
+fn parse_quantities(quantity: &str) -> Result<Vec<Quantity>, Error> {
+    let quantities: Vec<Quantity> = quantity
+        .split_whitespace()
+        .map(|q| {
+            if q.contains('/') {
+                Quantity::Fraction(q.to_string())
+            } else {
+                Quantity::Number(q.to_string())
+            }
+        })
+        .collect();
+    if quantities.is_empty() || quantities.iter().any(|q| f64::try_from(q).is_err()) {
+        return Err(Error::InvalidQuantity(quantity.to_string()));
+    }
+    Ok(quantities)
+}
+
+// Text must lex as a single sentence token on the line of its operator.
+fn check_text(text: &str) -> Result<(), Error> {
+    if text.contains(['\n', '\r']) {
+        return Err(Error::InvalidText(text.to_string()));
+    }
+    check_sentence(text)
+}
+
+// Text must lex as a single sentence token, without whitespace at its ends as parsing trims it.
+fn check_sentence(text: &str) -> Result<(), Error> {
+    let valid = match text.chars().next() {
+        Some(first) => {
+            !first.is_numeric()
+                && !first.is_whitespace()
+                && !text.ends_with(char::is_whitespace)
+                && !matches!(first, '<' | '^')
+                && !text.contains(['*', '#', '=', '>', ':'])
+        }
+        None => false,
+    };
+    if valid {
+        Ok(())
+    } else {
+        Err(Error::InvalidText(text.to_string()))
+    }
+}
+
+#[derive(Error, Debug)]
+#[non_exhaustive]
+pub enum Error {
+    #[error("no operand at path {0:?}")]
+    InvalidPath(Vec<usize>),
+    #[error("operand at path {0:?} is not an operator")]
+    NotAnOperator(Vec<usize>),
+    #[error("cannot move an operand into itself")]
+    MoveIntoSelf,
+    #[error("invalid quantity: {0}")]
+    InvalidQuantity(String),
+    #[error("invalid text: {0:?}")]
+    InvalidText(String),
+    #[error("ingredient has a unit without a quantity: {0}")]
+    UnitWithoutQuantity(String),
+    #[error("operator has no operands: {0}")]
+    EmptyOperator(String),
+    #[error("`+` operator cannot be an operand of a binary operator")]
+    NestedCombine,
+}
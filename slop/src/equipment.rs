@@ -0,0 +1,235 @@
+use std::fmt::Display;
+
+use crate::{
+    annotation::{AnnotationKind, Temperature},
+    menu::{Plan, PlannedStep},
+    semantic::{Operand, Recipe},
+};
+
+// Equipment used by the steps of recipes.
+//
+// Equipment is recognized by the words of operator texts, e.g. `bake` or `oven` for the oven and
+// `simmer` or `saucepan` for a pot. The vocabulary can be extended in `slop.toml`:
+//
+//     [equipment]
+//     wok = ["wok", "stir fry"]
+//     oven = { words = ["oven", "bake", "roast"], shared = true }
+//
+// Shared equipment can be used by several steps at once, like an oven holding two dishes, as long
+// as the steps don't need it at different temperatures.
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Vocabulary {
+    pub equipment: Vec<Equipment>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+#[non_exhaustive]
+pub struct Equipment {
+    pub name: String,
+    /// Lowercase words or phrases of operator texts that mention the equipment.
+    pub words: Vec<String>,
+    /// Whether steps can use the equipment at the same time.
+    pub shared: bool,
+}
+
+impl Equipment {
+    pub fn new(name: &str, words: &[&str], shared: bool) -> Self {
+        Self {
+            name: name.to_string(),
+            words: words.iter().map(|word| word.to_lowercase()).collect(),
+            shared,
+        }
+    }
+}
+
+impl Default for Vocabulary {
+    #[rustfmt::skip]
+    fn default() -> Self {
+        Self {
+            equipment: vec![
+                Equipment::new("oven", &["oven", "bake", "baked", "roast", "broil"], true),
+                Equipment::new("baking sheet", &["baking sheet", "sheet pan", "cookie sheet"], false),
+                Equipment::new("pan", &["pan", "skillet", "frying pan", "fry", "saute", "sauté"], false),
+                Equipment::new("pot", &["pot", "saucepan", "stockpot", "boil", "simmer"], false),
+                Equipment::new("double boiler", &["double boiler", "bain-marie", "bain marie"], false),
+                Equipment::new("steamer", &["steamer", "steam"], false),
+                Equipment::new("blender", &["blender", "blend", "puree", "purée"], false),
+                Equipment::new("food processor", &["food processor", "pulse"], false),
+                Equipment::new("stand mixer", &["stand mixer", "mixer"], false),
+                Equipment::new("grill", &["grill", "grilled"], false),
+                Equipment::new("microwave", &["microwave"], false),
+                Equipment::new("whisk", &["whisk"], true),
+            ],
+        }
+    }
+}
+
+impl Vocabulary {
+    /// Add equipment, replacing the equipment with the same name.
+    pub fn insert(&mut self, equipment: Equipment) {
+        match self.equipment.iter_mut().find(|e| e.name == equipment.name) {
+            Some(existing) => *existing = equipment,
+            None => self.equipment.push(equipment),
+        }
+    }
+
+    /// Equipment mentioned in an operator text, in the order it is mentioned. Longer phrases win
+    /// over the words they overlap, e.g. `sheet pan` is a baking sheet rather than a pan.
+    pub fn detect(&self, text: &str) -> Vec<&Equipment> {
+        let text = text.to_lowercase();
+        let mut matches: Vec<(std::ops::Range<usize>, &Equipment)> = self
+            .equipment
+            .iter()
+            .flat_map(|equipment| {
+                equipment
+                    .words
+                    .iter()
+                    .flat_map(|word| find_word(&text, word))
+                    .map(move |span| (span, equipment))
+            })
+            .collect();
+        matches.sort_by_key(|(span, _)| std::cmp::Reverse(span.len()));
+        let mut found: Vec<(std::ops::Range<usize>, &Equipment)> = Vec::new();
+        for (span, equipment) in matches {
+            if !found
+                .iter()
+                .any(|(other, _)| other.start < span.end && span.start < other.end)
+            {
+                found.push((span, equipment));
+            }
+        }
+        found.sort_by_key(|(span, _)| span.start);
+        let mut equipment: Vec<&Equipment> = Vec::new();
+        for (_, e) in found {
+            if !equipment.contains(&e) {
+                equipment.push(e);
+            }
+        }
+        equipment
+    }
+
+    /// Equipment used by a step.
+    pub fn step(&self, op: &Operand) -> Vec<&Equipment> {
+        op.text().map(|text| self.detect(text)).unwrap_or_default()
+    }
+}
+
+// Occurrences of the word in the text that aren't part of a longer word.
+fn find_word<'a>(
+    text: &'a str,
+    word: &'a str,
+) -> impl Iterator<Item = std::ops::Range<usize>> + 'a {
+    text.match_indices(word)
+        .map(|(start, word)| start..start + word.len())
+        .filter(|span| {
+            let before = text[..span.start].chars().next_back();
+            let after = text[span.end..].chars().next();
+            !before.is_some_and(char::is_alphanumeric) && !after.is_some_and(char::is_alphanumeric)
+        })
+}
+
+/// Equipment needed to cook the recipe, in the order it is first used.
+pub fn checklist<'v>(recipe: &Recipe, vocabulary: &'v Vocabulary) -> Vec<&'v Equipment> {
+    let mut equipment: Vec<&Equipment> = Vec::new();
+    for step in recipe.steps() {
+        for e in vocabulary.step(step) {
+            if !equipment.contains(&e) {
+                equipment.push(e);
+            }
+        }
+    }
+    equipment
+}
+
+/// Two steps of a plan that need the same equipment at overlapping times.
+#[derive(Clone, Debug, PartialEq)]
+#[non_exhaustive]
+pub struct Conflict<'p, 'a> {
+    pub equipment: String,
+    pub first: &'p PlannedStep<'a>,
+    pub second: &'p PlannedStep<'a>,
+    pub kind: ConflictKind,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+#[non_exhaustive]
+pub enum ConflictKind {
+    /// The equipment can't be shared, see [`Equipment::shared`].
+    InUse,
+    /// The steps need the equipment at different temperatures.
+    Temperature(Temperature, Temperature),
+}
+
+/// Find steps of the plan that need the same equipment at the same time.
+pub fn conflicts<'p, 'a>(plan: &'p Plan<'a>, vocabulary: &Vocabulary) -> Vec<Conflict<'p, 'a>> {
+    let equipment: Vec<Vec<&Equipment>> = plan
+        .steps
+        .iter()
+        .map(|step| vocabulary.step(step.operator))
+        .collect();
+    let mut conflicts = Vec::new();
+    for (i, first) in plan.steps.iter().enumerate() {
+        // Steps are ordered by their start, so only the following steps can start during this one.
+        for (j, second) in plan.steps.iter().enumerate().skip(i + 1) {
+            if second.start >= first.end {
+                break;
+            }
+            for e in equipment[i].iter().filter(|e| equipment[j].contains(e)) {
+                let kind = match (temperature(first.operator), temperature(second.operator)) {
+                    (Some(a), Some(b)) if a.fahrenheit().round() != b.fahrenheit().round() => {
+                        ConflictKind::Temperature(a, b)
+                    }
+                    _ if !e.shared => ConflictKind::InUse,
+                    _ => continue,
+                };
+                conflicts.push(Conflict {
+                    equipment: e.name.clone(),
+                    first,
+                    second,
+                    kind,
+                });
+            }
+        }
+    }
+    conflicts
+}
+
+fn temperature(op: &Operand) -> Option<Temperature> {
+    op.annotations()
+        .iter()
+        .find_map(|annotation| match &annotation.kind {
+            AnnotationKind::Temperature(temperature) => Some(*temperature),
+            _ => None,
+        })
+}
+
+impl Display for Conflict<'_, '_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let describe = |step: &PlannedStep| {
+            format!(
+                "{} `{}` ({}-{})",
+                step.recipe.title().unwrap_or("Untitled"),
+                step.text(),
+                step.start,
+                step.end
+            )
+        };
+        match &self.kind {
+            ConflictKind::InUse => write!(
+                f,
+                "{} and {} both need the {}",
+                describe(self.first),
+                describe(self.second),
+                self.equipment
+            ),
+            ConflictKind::Temperature(a, b) => write!(
+                f,
+                "{} and {} need the {} at {a} and {b}",
+                describe(self.first),
+                describe(self.second),
+                self.equipment
+            ),
+        }
+    }
+}
@@ -0,0 +1,179 @@
+use crate::{
+    ast::Position,
+    compile,
+    diagnostic::{codes, Diagnostic, Fix, TextEdit},
+    lint::{self, LintConfig},
+    parse,
+    quant::{canonical_unit, suggest_unit},
+    semantic::{Operand, SourceFile},
+    visit::semantic::{walk_operand, Visit},
+};
+
+// Machine applicable fixes for diagnostics.
+//
+// Diagnostics are produced without access to the source, so fixes are computed afterwards from
+// the source and the compiled file and attached to the diagnostic they resolve. Every fix is a
+// set of edits against the original source.
+
+/// Ingredient inserted where an operand is missing.
+pub const PLACEHOLDER: &str = "*ingredient ";
+
+// Passes made by fix before giving up, each pass can only fix what the previous one uncovered.
+const MAX_PASSES: usize = 8;
+
+/// Attach fixes to the diagnostics that have one.
+pub fn suggest(src: &str, file: &SourceFile, diagnostics: &mut [Diagnostic]) {
+    for diagnostic in diagnostics {
+        let fix = match diagnostic.code {
+            codes::UNUSED_OPERANDS => combine_unused(src, file, diagnostic),
+            codes::MISSING_OPERAND => insert_placeholder(diagnostic),
+            lint::codes::NON_CANONICAL_UNIT => replace_unit(src, diagnostic, canonical_unit),
+            lint::codes::UNKNOWN_UNIT => replace_unit(src, diagnostic, suggest_unit),
+            _ => None,
+        };
+        diagnostic.fixes.extend(fix);
+    }
+}
+
+/// Apply the fixes to the source.
+///
+/// A fix is skipped entirely when any of its edits overlaps an edit of an earlier fix.
+pub fn apply<'a>(src: &str, fixes: impl IntoIterator<Item = &'a Fix>) -> String {
+    let mut edits: Vec<&TextEdit> = Vec::new();
+    for fix in fixes {
+        let conflicts = fix.edits.iter().any(|edit| {
+            edits.iter().any(|other| {
+                edit.span.start == other.span.start
+                    || (edit.span.start < other.span.end && other.span.start < edit.span.end)
+            })
+        });
+        if !conflicts {
+            edits.extend(&fix.edits);
+        }
+    }
+    edits.sort_by_key(|edit| edit.span.start);
+
+    let mut out = String::with_capacity(src.len());
+    let mut last = 0;
+    for edit in edits {
+        out.push_str(&src[last..edit.span.start]);
+        out.push_str(&edit.text);
+        last = edit.span.end;
+    }
+    out.push_str(&src[last..]);
+    out
+}
+
+/// Apply the first fix of every diagnostic until nothing is left to fix.
+///
+/// Returns the fixed source and the diagnostics that remain.
+pub fn fix(src: &str, config: &LintConfig) -> (String, Vec<Diagnostic>) {
+    let mut src = src.to_string();
+    let mut diagnostics = diagnose(&src, config);
+    for _ in 0..MAX_PASSES {
+        let fixed = apply(&src, diagnostics.iter().filter_map(|d| d.fixes.first()));
+        if fixed == src {
+            break;
+        }
+        src = fixed;
+        diagnostics = diagnose(&src, config);
+    }
+    (src, diagnostics)
+}
+
+// Compile and lint the source with fixes attached.
+fn diagnose(src: &str, config: &LintConfig) -> Vec<Diagnostic> {
+    let (file, errors) = compile(src);
+    let (syntax, _) = parse(src);
+    let mut diagnostics: Vec<Diagnostic> = errors
+        .into_iter()
+        .chain(lint::lint(&syntax, &file, config))
+        .collect();
+    suggest(src, &file, &mut diagnostics);
+    diagnostics
+}
+
+// Insert `#+` after every unused operand but the first, combining them into one operand.
+fn combine_unused(src: &str, file: &SourceFile, diagnostic: &Diagnostic) -> Option<Fix> {
+    let mut finder = UnusedFinder {
+        span: diagnostic.span(),
+        operands: None,
+    };
+    finder.visit_source_file(file);
+    let operands = finder.operands?;
+    let edits = operands
+        .iter()
+        .skip(1)
+        .map(|op| {
+            let end = trimmed_end(src, op.position().end);
+            TextEdit {
+                span: end..end,
+                text: " #+".to_string(),
+            }
+        })
+        .collect::<Vec<_>>();
+    if edits.is_empty() {
+        return None;
+    }
+    Some(Fix {
+        message: "combine the operands with `#+`".to_string(),
+        edits,
+    })
+}
+
+struct UnusedFinder<'a> {
+    span: &'a Position,
+    operands: Option<&'a Vec<Operand>>,
+}
+
+impl<'a> Visit<'a> for UnusedFinder<'a> {
+    fn visit_operand(&mut self, op: &'a Operand) {
+        if let Operand::UnusedOperands { position, operands } = op {
+            if position == self.span {
+                self.operands = Some(operands);
+            }
+        }
+        walk_operand(self, op)
+    }
+}
+
+// Insert a placeholder ingredient before the operator missing an operand.
+fn insert_placeholder(diagnostic: &Diagnostic) -> Option<Fix> {
+    let start = diagnostic.span().start;
+    Some(Fix {
+        message: "insert a placeholder ingredient".to_string(),
+        edits: vec![TextEdit {
+            span: start..start,
+            text: PLACEHOLDER.to_string(),
+        }],
+    })
+}
+
+// Replace the last word of the unit of the ingredient with the replacement for it.
+fn replace_unit(
+    src: &str,
+    diagnostic: &Diagnostic,
+    replacement: fn(&str) -> Option<&'static str>,
+) -> Option<Fix> {
+    let span = diagnostic.span();
+    let ingredient = src.get(span.clone())?;
+    let measure = ingredient[..ingredient.find(':')?].trim_end();
+    let start = measure
+        .rfind(|c: char| c.is_whitespace() || c == '*' || c == '^')
+        .map_or(0, |i| i + 1);
+    let unit = &measure[start..];
+    let replacement = replacement(unit)?;
+    let start = span.start + start;
+    Some(Fix {
+        message: format!("replace `{unit}` with `{replacement}`"),
+        edits: vec![TextEdit {
+            span: start..start + unit.len(),
+            text: replacement.to_string(),
+        }],
+    })
+}
+
+// Offset of the end of the text before `end`, ignoring trailing whitespace.
+fn trimmed_end(src: &str, end: usize) -> usize {
+    src[..end.min(src.len())].trim_end().len()
+}
@@ -0,0 +1,159 @@
+use crate::semantic::{Operand, Recipe};
+
+// Rendering of the tree of a recipe as a flowchart in the Graphviz DOT and Mermaid languages.
+//
+// Ingredients are the leaves and steps are the nodes they flow into, edges point from an operand
+// to the step using it so the finished dish is at the end of the chart. A derived `^` ingredient
+// is the by product of an earlier step, which step isn't part of the source, so it is a leaf with
+// a dashed outline and a dashed edge. Missing and unused operands are nodes of their own so the
+// errors of a recipe show up in its chart.
+
+/// Render the recipe as a Graphviz digraph, laid out from left to right.
+pub fn to_dot(recipe: &Recipe) -> String {
+    let (nodes, edges) = graph(recipe);
+    let mut out = format!(
+        "digraph \"{}\" {{\n    rankdir=LR;\n    node [fontname=\"sans-serif\"];\n",
+        escape_dot(recipe.title().unwrap_or("Untitled"))
+    );
+    for (id, node) in nodes.iter().enumerate() {
+        let shape = match node.kind {
+            Kind::Ingredient => "shape=box",
+            Kind::Derived => "shape=box, style=dashed",
+            Kind::Step => "shape=ellipse",
+            Kind::Error => "shape=octagon, color=red",
+        };
+        out += &format!(
+            "    n{} [label=\"{}\", {shape}];\n",
+            id + 1,
+            escape_dot(&node.text)
+        );
+    }
+    for edge in &edges {
+        let style = if edge.dashed { " [style=dashed]" } else { "" };
+        out += &format!("    n{} -> n{}{style};\n", edge.from + 1, edge.to + 1);
+    }
+    out + "}\n"
+}
+
+/// Render the recipe as a Mermaid flowchart, laid out from left to right.
+pub fn to_mermaid(recipe: &Recipe) -> String {
+    let (nodes, edges) = graph(recipe);
+    let mut out = String::new();
+    if let Some(title) = recipe.title() {
+        out += &format!(
+            "---\ntitle: \"{}\"\n---\n",
+            title.replace('\\', "\\\\").replace('"', "\\\"")
+        );
+    }
+    out += "flowchart LR\n";
+    let mut derived = Vec::new();
+    for (id, node) in nodes.iter().enumerate() {
+        let text = node.text.replace('"', "#quot;");
+        let node = match node.kind {
+            Kind::Ingredient => format!("[\"{text}\"]"),
+            Kind::Derived => {
+                derived.push(format!("n{}", id + 1));
+                format!("[\"{text}\"]")
+            }
+            Kind::Step => format!("(\"{text}\")"),
+            Kind::Error => format!("{{{{\"{text}\"}}}}"),
+        };
+        out += &format!("    n{}{node}\n", id + 1);
+    }
+    for edge in &edges {
+        let arrow = if edge.dashed { "-.->" } else { "-->" };
+        out += &format!("    n{} {arrow} n{}\n", edge.from + 1, edge.to + 1);
+    }
+    if !derived.is_empty() {
+        out += "    classDef derived stroke-dasharray: 4 4\n";
+        out += &format!("    class {} derived\n", derived.join(","));
+    }
+    out
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Kind {
+    Ingredient,
+    Derived,
+    Step,
+    Error,
+}
+
+struct Node {
+    text: String,
+    kind: Kind,
+}
+
+// An edge from an operand to the step using it.
+struct Edge {
+    from: usize,
+    to: usize,
+    dashed: bool,
+}
+
+// The nodes of the recipe with the root first and the edges between them.
+fn graph(recipe: &Recipe) -> (Vec<Node>, Vec<Edge>) {
+    let mut nodes = Vec::new();
+    let mut edges = Vec::new();
+    add(recipe.root(), &mut nodes, &mut edges);
+    (nodes, edges)
+}
+
+// Add the node of the operand and those under it, returning the index of its node.
+fn add(op: &Operand, nodes: &mut Vec<Node>, edges: &mut Vec<Edge>) -> usize {
+    let (node, operands): (Node, &[Operand]) = match op {
+        Operand::Ingredient(ingredient) if ingredient.is_derived() => (
+            Node {
+                text: ingredient.text().to_string(),
+                kind: Kind::Derived,
+            },
+            &[],
+        ),
+        Operand::Ingredient(ingredient) => (
+            Node {
+                text: ingredient.to_string(),
+                kind: Kind::Ingredient,
+            },
+            &[],
+        ),
+        Operand::Operator { text, operands, .. } => (
+            Node {
+                text: match text.trim() {
+                    "" | "+" => "combine".to_string(),
+                    text => text.to_string(),
+                },
+                kind: Kind::Step,
+            },
+            operands,
+        ),
+        Operand::UnusedOperands { operands, .. } => (
+            Node {
+                text: "unused operands".to_string(),
+                kind: Kind::Error,
+            },
+            operands,
+        ),
+        Operand::MissingOperand { .. } => (
+            Node {
+                text: "missing operand".to_string(),
+                kind: Kind::Error,
+            },
+            &[],
+        ),
+    };
+    nodes.push(node);
+    let id = nodes.len() - 1;
+    for operand in operands {
+        let from = add(operand, nodes, edges);
+        edges.push(Edge {
+            from,
+            to: id,
+            dashed: nodes[from].kind == Kind::Derived,
+        });
+    }
+    id
+}
+
+fn escape_dot(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('"', "\\\"")
+}
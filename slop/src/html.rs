@@ -0,0 +1,211 @@
+use crate::{
+    prose::{self, escape_html},
+    semantic::{Operand, Recipe},
+};
+
+// Rendering of recipes as standalone semantic HTML.
+//
+// Each recipe is an `<article>` with an ingredient table, the numbered sentences of `prose` and a
+// tree card in the style of Cooking for Engineers. The card is a table with one row per
+// ingredient and a column per level of steps: a step spans the rows of the ingredients it
+// combines and the columns up to the step using its result. Ingredients are row headers and
+// every step lists the ingredients under it in its `headers` attribute, so screen readers announce
+// what a step combines.
+
+const STYLE: &str = "\
+body { font-family: sans-serif; max-width: 60em; margin: 0 auto; padding: 1em; }
+table { border-collapse: collapse; margin: 1em 0; }
+caption { font-weight: bold; text-align: left; }
+th, td { border: 1px solid #444; padding: 0.25em 0.5em; text-align: left; }
+.ingredients td:first-child { text-align: right; }
+.card th { font-weight: normal; }
+.card td { background: #eee; }
+.card .derived { font-style: italic; }
+@media print { .recipe { break-inside: avoid; } }
+";
+
+/// Render the recipes as a standalone HTML document.
+pub fn to_html(recipes: &[Recipe]) -> String {
+    let title = recipes.first().and_then(Recipe::title).unwrap_or("Recipes");
+    let articles: Vec<String> = recipes
+        .iter()
+        .enumerate()
+        .map(|(i, recipe)| article(recipe, &format!("recipe-{}", i + 1)))
+        .collect();
+    document(title, "", &articles.join(""))
+}
+
+// Wrap the body in a standalone document, `style` is added after the rules of `STYLE`.
+pub(crate) fn document(title: &str, style: &str, body: &str) -> String {
+    format!(
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n\
+         <title>{}</title>\n<style>\n{STYLE}{style}</style>\n</head>\n<body>\n{body}</body>\n</html>\n",
+        escape_html(title)
+    )
+}
+
+/// Render the recipe as an `<article>`, `id` must be unique in the document as the ids of the
+/// card cells are derived from it.
+pub fn article(recipe: &Recipe, id: &str) -> String {
+    let instructions = prose::instructions(recipe);
+    let mut out = format!("<article class=\"recipe\" id=\"{}\">\n", escape_html(id));
+    if let Some(title) = &instructions.title {
+        out += &format!("<h1>{}</h1>\n", escape_html(title));
+    }
+    if let Some(preamble) = &instructions.preamble {
+        out += &format!("<p>{}</p>\n", escape_html(preamble));
+    }
+    out += "<table class=\"ingredients\">\n<caption>Ingredients</caption>\n<thead>\n\
+            <tr><th scope=\"col\">Quantity</th><th scope=\"col\">Unit</th>\
+            <th scope=\"col\">Ingredient</th></tr>\n</thead>\n<tbody>\n";
+    for ingredient in recipe.ingredients() {
+        if ingredient.is_derived() {
+            continue;
+        }
+        out += &format!(
+            "<tr><td>{}</td><td>{}</td><td>{}</td></tr>\n",
+            escape_html(ingredient.quantity_text().unwrap_or_default()),
+            escape_html(ingredient.unit().unwrap_or_default()),
+            escape_html(ingredient.text())
+        );
+    }
+    out += "</tbody>\n</table>\n<h2>Instructions</h2>\n<ol>\n";
+    for step in &instructions.steps {
+        out += &format!("<li>{}</li>\n", escape_html(step));
+    }
+    out += "</ol>\n";
+    out += &card(recipe, id);
+    if let Some(comment) = &instructions.comment {
+        out += &format!(
+            "<p class=\"comment\"><em>{}</em></p>\n",
+            escape_html(comment)
+        );
+    }
+    out + "</article>\n"
+}
+
+/// Render the tree card of the recipe as a table, see the module comment for the layout.
+pub fn card(recipe: &Recipe, id: &str) -> String {
+    let cells = cells(recipe);
+    let id = escape_html(id);
+    let cell_id = |index: usize| format!("{id}-cell-{}", index + 1);
+
+    let mut order: Vec<usize> = (0..cells.len()).collect();
+    order.sort_by_key(|&index| (cells[index].row, cells[index].column));
+    let mut out = String::from("<table class=\"card\">\n<caption>Recipe card</caption>\n<tbody>\n");
+    let mut row = None;
+    for index in order {
+        let cell = &cells[index];
+        if row != Some(cell.row) {
+            if row.is_some() {
+                out += "</tr>\n";
+            }
+            out += "<tr>";
+            row = Some(cell.row);
+        }
+        let mut attributes = format!(" id=\"{}\"", cell_id(index));
+        if cell.rowspan > 1 {
+            attributes += &format!(" rowspan=\"{}\"", cell.rowspan);
+        }
+        if cell.colspan > 1 {
+            attributes += &format!(" colspan=\"{}\"", cell.colspan);
+        }
+        if cell.derived {
+            attributes += " class=\"derived\"";
+        }
+        let text = escape_html(&cell.text);
+        if cell.headers.is_empty() {
+            out += &format!("<th scope=\"row\"{attributes}>{text}</th>");
+        } else {
+            let headers: Vec<String> = cell.headers.iter().map(|&h| cell_id(h)).collect();
+            out += &format!(
+                "<td{attributes} headers=\"{}\">{text}</td>",
+                headers.join(" ")
+            );
+        }
+    }
+    out + "</tr>\n</tbody>\n</table>\n"
+}
+
+// A cell of the card.
+pub(crate) struct Cell {
+    pub(crate) row: usize,
+    pub(crate) column: usize,
+    pub(crate) rowspan: usize,
+    pub(crate) colspan: usize,
+    pub(crate) text: String,
+    pub(crate) derived: bool,
+    /// The ingredient cells under a step, empty for ingredients which head their row.
+    pub(crate) headers: Vec<usize>,
+}
+
+// The cells of the card of the recipe, see the module comment for the layout.
+pub(crate) fn cells(recipe: &Recipe) -> Vec<Cell> {
+    let mut cells = Vec::new();
+    let mut rows = 0;
+    let root = layout(recipe.root(), &mut cells, &mut rows);
+    cells[root].colspan = 1;
+    cells
+}
+
+// Lay out the cells of the operand and the operands under it, returning the index of its cell.
+fn layout(op: &Operand, cells: &mut Vec<Cell>, rows: &mut usize) -> usize {
+    let (text, operands): (String, &[Operand]) = match op {
+        Operand::Ingredient(ingredient) => {
+            cells.push(Cell {
+                row: *rows,
+                column: 0,
+                rowspan: 1,
+                colspan: 1,
+                text: if ingredient.is_derived() {
+                    ingredient.text().to_string()
+                } else {
+                    ingredient.to_string()
+                },
+                derived: ingredient.is_derived(),
+                headers: Vec::new(),
+            });
+            *rows += 1;
+            return cells.len() - 1;
+        }
+        Operand::Operator { text, operands, .. } => match text.trim() {
+            "" | "+" => ("combine".to_string(), operands),
+            text => (text.to_string(), operands),
+        },
+        Operand::UnusedOperands { operands, .. } => ("unused".to_string(), operands),
+        Operand::MissingOperand { .. } => ("missing".to_string(), &[]),
+    };
+    let row = *rows;
+    let children: Vec<usize> = operands
+        .iter()
+        .map(|operand| layout(operand, cells, rows))
+        .collect();
+    let column = children
+        .iter()
+        .map(|&child| cells[child].column + 1)
+        .max()
+        .unwrap_or(0);
+    let mut headers = Vec::new();
+    for &child in &children {
+        cells[child].colspan = column - cells[child].column;
+        if cells[child].headers.is_empty() {
+            headers.push(child);
+        } else {
+            headers.extend(cells[child].headers.clone());
+        }
+    }
+    // Steps without operands take a row of their own.
+    if *rows == row {
+        *rows += 1;
+    }
+    cells.push(Cell {
+        row,
+        column,
+        rowspan: *rows - row,
+        colspan: 1,
+        text,
+        derived: false,
+        headers,
+    });
+    cells.len() - 1
+}
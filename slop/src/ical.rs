@@ -0,0 +1,206 @@
+use std::{fmt::Display, str::FromStr};
+
+use thiserror::Error;
+
+use crate::{
+    menu::{Clock, Plan, PlannedStep},
+    semantic::Operand,
+};
+
+// Export of a menu schedule as an iCalendar file (RFC 5545).
+//
+// Every step of the plan becomes a VEVENT with a display alarm at its start. Times are written
+// as floating local times, so the events stay at the planned time of day in whatever time zone
+// the calendar app is in. The description of an event lists the ingredients the step uses
+// directly. The UID of an event is its serve time, a hash of the titles of the recipes on the menu
+// and its position, so exporting the same menu again updates its events rather than those of
+// another menu served at the same time.
+
+/// A calendar date, the day a menu is served.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Date {
+    pub year: i64,
+    pub month: u32,
+    pub day: u32,
+}
+
+#[derive(Error, Debug, PartialEq)]
+#[error("invalid date `{0}`, expected a date like 2024-03-31")]
+pub struct ParseDateError(String);
+
+impl Date {
+    /// The date `days` days after this one, earlier for negative `days`.
+    pub fn add_days(&self, days: i64) -> Self {
+        Self::from_days(self.days() + days)
+    }
+    // Days since 1970-01-01, see http://howardhinnant.github.io/date_algorithms.html
+    fn days(&self) -> i64 {
+        let (month, day) = (i64::from(self.month), i64::from(self.day));
+        let year = if month <= 2 { self.year - 1 } else { self.year };
+        let era = year.div_euclid(400);
+        let year_of_era = year - era * 400;
+        let day_of_year = (153 * (month + if month > 2 { -3 } else { 9 }) + 2) / 5 + day - 1;
+        let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+        era * 146097 + day_of_era - 719468
+    }
+    fn from_days(days: i64) -> Self {
+        let days = days + 719468;
+        let era = days.div_euclid(146097);
+        let day_of_era = days - era * 146097;
+        let year_of_era =
+            (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+        let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+        let mp = (5 * day_of_year + 2) / 153;
+        let day = (day_of_year - (153 * mp + 2) / 5 + 1) as u32;
+        let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+        let year = year_of_era + era * 400 + i64::from(month <= 2);
+        Self { year, month, day }
+    }
+}
+
+impl FromStr for Date {
+    type Err = ParseDateError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || ParseDateError(s.to_string());
+        let mut parts = s.trim().splitn(3, '-');
+        let mut next = || parts.next().ok_or_else(error);
+        let year = next()?.parse().map_err(|_| error())?;
+        let month = next()?.parse().map_err(|_| error())?;
+        let day = next()?.parse().map_err(|_| error())?;
+        let date = Date { year, month, day };
+        // Reject days that don't exist, e.g. 2023-02-29, by checking they survive a round trip.
+        if !(1..=12).contains(&month) || Date::from_days(date.days()) != date {
+            return Err(error());
+        }
+        Ok(date)
+    }
+}
+
+impl Display for Date {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+}
+
+/// Render the plan as an iCalendar file for a menu served on `date`. With `timed_only` steps
+/// without a duration are left out.
+pub fn to_ical(plan: &Plan, date: Date, timed_only: bool) -> String {
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        "PRODID:-//slop//slop//EN".to_string(),
+        "CALSCALE:GREGORIAN".to_string(),
+    ];
+    // Calendars require a creation time, the serve date keeps the output reproducible.
+    let stamp = format!("{}Z", date_time(date, Clock::new(0, 0)));
+    let menu = menu_hash(plan);
+    for (index, step) in plan.steps.iter().enumerate() {
+        if timed_only && step.start == step.end {
+            continue;
+        }
+        let summary = format!(
+            "{}: {}",
+            step.recipe.title().unwrap_or("Untitled"),
+            step.text()
+        );
+        lines.extend([
+            "BEGIN:VEVENT".to_string(),
+            format!(
+                "UID:{}-{menu:016x}-{index}@slop",
+                date_time(date, plan.serve)
+            ),
+            format!("DTSTAMP:{stamp}"),
+            format!("DTSTART:{}", date_time(date, step.start)),
+            format!("DTEND:{}", date_time(date, step.end)),
+            format!("SUMMARY:{}", escape(&summary)),
+        ]);
+        let description = describe(step);
+        if !description.is_empty() {
+            lines.push(format!("DESCRIPTION:{}", escape(&description)));
+        }
+        if step.passive {
+            // Waiting doesn't keep the cook busy.
+            lines.push("TRANSP:TRANSPARENT".to_string());
+        }
+        lines.extend([
+            "BEGIN:VALARM".to_string(),
+            "ACTION:DISPLAY".to_string(),
+            "TRIGGER:PT0S".to_string(),
+            format!("DESCRIPTION:{}", escape(&summary)),
+            "END:VALARM".to_string(),
+            "END:VEVENT".to_string(),
+        ]);
+    }
+    lines.push("END:VCALENDAR".to_string());
+    lines.iter().map(|line| fold(line) + "\r\n").collect()
+}
+
+// Ingredients used directly by the step, one per line.
+fn describe(step: &PlannedStep) -> String {
+    step.operator
+        .operands()
+        .iter()
+        .filter_map(|operand| match operand {
+            Operand::Ingredient(ingredient) => Some(ingredient.to_string()),
+            _ => None,
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+// FNV-1a hash of the titles of the recipes of the plan, stable across builds unlike `std::hash`.
+fn menu_hash(plan: &Plan) -> u64 {
+    let mut titles: Vec<&str> = Vec::new();
+    for step in &plan.steps {
+        let title = step.recipe.title().unwrap_or("Untitled");
+        if !titles.contains(&title) {
+            titles.push(title);
+        }
+    }
+    titles
+        .join("\n")
+        .bytes()
+        .fold(0xcbf29ce484222325, |hash, byte| {
+            (hash ^ u64::from(byte)).wrapping_mul(0x100000001b3)
+        })
+}
+
+fn date_time(date: Date, clock: Clock) -> String {
+    let date = date.add_days(clock.secs().div_euclid(86400));
+    let secs = clock.secs().rem_euclid(86400);
+    format!(
+        "{:04}{:02}{:02}T{:02}{:02}{:02}",
+        date.year,
+        date.month,
+        date.day,
+        secs / 3600,
+        secs / 60 % 60,
+        secs % 60
+    )
+}
+
+// Escape text values, line breaks of any kind become `\n` as a bare CR would end the line.
+fn escape(text: &str) -> String {
+    text.replace("\r\n", "\n")
+        .replace('\r', "\n")
+        .replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace('\n', "\\n")
+}
+
+// Fold lines longer than 75 octets, continuation lines start with a space.
+fn fold(line: &str) -> String {
+    let mut folded = String::new();
+    let mut length = 0;
+    for c in line.chars() {
+        if length + c.len_utf8() > 75 {
+            folded.push_str("\r\n ");
+            length = 1;
+        }
+        folded.push(c);
+        length += c.len_utf8();
+    }
+    folded
+}
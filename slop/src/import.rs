@@ -0,0 +1,276 @@
+use once_cell::sync::Lazy;
+use regex::Regex;
+use thiserror::Error;
+
+use crate::{
+    edit,
+    quant::is_known_unit,
+    semantic::{Ingredient, Operand, Recipe},
+};
+
+// Import of recipes written in other formats.
+//
+// Other formats describe a recipe as a list of ingredients and a list of instructions rather than
+// a tree. Importers fill in a `Draft` and the tree is guessed from it: each step applies to the
+// result of the step before and the ingredients it uses, ingredients not assigned to a step go
+// into the first step. The guesses are recorded as TODO notes on lines of the final comment of
+// the generated source, after the yield, so they can be reviewed. Renderings leave TODO lines of
+// the comment out.
+//
+// Collections such as Paprika archives and MealMaster files hold many recipes, each is converted
+// on its own so that one bad recipe doesn't stop the import of the others.
+
+pub mod cooklang;
+pub mod mealmaster;
+#[cfg(feature = "paprika")]
+pub mod paprika;
+#[cfg(feature = "serde")]
+pub mod schema_org;
+pub mod text;
+
+#[derive(Error, Debug)]
+#[non_exhaustive]
+pub enum Error {
+    #[cfg(feature = "serde")]
+    #[error("invalid JSON: {0}")]
+    Json(#[from] serde_json::Error),
+    #[cfg(feature = "paprika")]
+    #[error("invalid archive: {0}")]
+    Archive(#[from] zip::result::ZipError),
+    #[error("reading recipe: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("no recipe found")]
+    NoRecipe,
+    #[error("generating source: {0}")]
+    Edit(#[from] edit::Error),
+}
+
+/// A recipe of a collection converted into slop source.
+#[derive(Debug)]
+#[non_exhaustive]
+pub struct Imported {
+    /// The title of the recipe, or its name in the collection when it has none.
+    pub name: String,
+    /// The formatted source, or why the recipe couldn't be converted.
+    pub result: Result<String, Error>,
+}
+
+impl Imported {
+    fn new(name: String, draft: Result<Draft, Error>) -> Self {
+        Self {
+            name,
+            result: draft.and_then(|draft| to_source(vec![draft.into_recipe()])),
+        }
+    }
+}
+
+/// A recipe read from another format, before its tree is built.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub(crate) struct Draft {
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub recipe_yield: Option<String>,
+    /// Ingredients not used by a particular step.
+    pub ingredients: Vec<Ingredient>,
+    pub steps: Vec<DraftStep>,
+    /// Notes about guesses made while importing.
+    pub notes: Vec<String>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub(crate) struct DraftStep {
+    pub text: String,
+    pub ingredients: Vec<Ingredient>,
+}
+
+impl Draft {
+    /// Build the recipe tree, see the module comment for the guesses made.
+    pub fn into_recipe(mut self) -> Recipe {
+        if self.steps.is_empty() {
+            self.notes
+                .push("TODO the recipe has no instructions, describe the steps".to_string());
+            self.steps.push(DraftStep {
+                text: "combine".to_string(),
+                ingredients: Vec::new(),
+            });
+        }
+        if !self.ingredients.is_empty() && self.steps.len() > 1 {
+            self.notes.push(
+                "TODO the ingredients were all added in the first step, move them to the steps \
+                 that use them"
+                    .to_string(),
+            );
+        }
+        let mut ingredients = std::mem::take(&mut self.ingredients);
+        ingredients.append(&mut self.steps[0].ingredients);
+        self.steps[0].ingredients = ingredients;
+        if self.steps.iter().all(|step| step.ingredients.is_empty()) {
+            self.notes
+                .push("TODO the recipe has no ingredients, add them".to_string());
+            self.steps[0].ingredients.push(
+                edit::ingredient(None, None, "ingredients").expect("ingredient should be valid"),
+            );
+        }
+
+        let mut root: Option<Operand> = None;
+        // Steps before any ingredient is used, e.g. preheating the oven, join the next step.
+        let mut pending: Vec<String> = Vec::new();
+        for step in self.steps {
+            let operands: Vec<Operand> = root
+                .take()
+                .into_iter()
+                .chain(step.ingredients.into_iter().map(Operand::Ingredient))
+                .collect();
+            let text = step.text.trim().trim_end_matches('.');
+            // Later steps continue the sentence, e.g. `preheat the oven, then mix`.
+            pending.push(if pending.is_empty() {
+                text.to_string()
+            } else {
+                lowercase_first(text)
+            });
+            if operands.is_empty() {
+                continue;
+            }
+            root = Some(edit::operator(
+                &sanitize(&pending.join(", then ")),
+                operands,
+            ));
+            pending.clear();
+        }
+        let preamble = self.description.as_deref().map(sentence);
+        // The notes follow the yield on lines of their own so they aren't taken as the yield.
+        let comment: Vec<String> = self
+            .recipe_yield
+            .map(|recipe_yield| sanitize(&format!("Makes {recipe_yield}")))
+            .into_iter()
+            .chain(self.notes.iter().map(|note| sentence(note)))
+            .collect();
+        edit::recipe(
+            self.title.as_deref().map(sanitize).as_deref(),
+            preamble.as_deref(),
+            (!comment.is_empty()).then(|| comment.join("\n")).as_deref(),
+            root.expect("there is at least one step"),
+        )
+    }
+}
+
+// The text sanitized and ended with a full stop.
+fn sentence(text: &str) -> String {
+    let text = sanitize(text);
+    if text.ends_with(['.', '!', '?']) {
+        text
+    } else {
+        text + "."
+    }
+}
+
+fn lowercase_first(text: &str) -> String {
+    let mut chars = text.chars();
+    match chars.next() {
+        Some(first) => first.to_lowercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+/// Generate formatted source for the recipes.
+pub(crate) fn to_source(recipes: Vec<Recipe>) -> Result<String, Error> {
+    if recipes.is_empty() {
+        return Err(Error::NoRecipe);
+    }
+    Ok(edit::to_source(&edit::source_file(recipes))?)
+}
+
+static QUANTITY: Lazy<Regex> = Lazy::new(|| {
+    let number = r"\d+/\d+|\d+(?:\.\d+)?(?:\s+\d+/\d+)?";
+    Regex::new(&format!(
+        r"^\s*({number})(?:\s*(?:-|–|to)\s*({number}))?\s*(.*)$"
+    ))
+    .unwrap()
+});
+
+const VULGAR_FRACTIONS: &[(char, &str)] = &[
+    ('½', "1/2"),
+    ('⅓', "1/3"),
+    ('⅔', "2/3"),
+    ('¼', "1/4"),
+    ('¾', "3/4"),
+    ('⅛', "1/8"),
+    ('⅜', "3/8"),
+    ('⅝', "5/8"),
+    ('⅞', "7/8"),
+];
+
+/// Parse an ingredient line such as `1 1/2 cups flour, sifted` into its quantity, unit and name.
+///
+/// Returns a note when part of the line had to be guessed, e.g. for a range of quantities.
+pub(crate) fn parse_ingredient(line: &str) -> (Ingredient, Option<String>) {
+    let mut line = line.trim().to_string();
+    for (fraction, text) in VULGAR_FRACTIONS {
+        line = line.replace(*fraction, &format!(" {text}"));
+    }
+    let mut note = None;
+    let (quantity, rest) = match QUANTITY.captures(&line) {
+        Some(captures) => {
+            if let Some(upper) = captures.get(2) {
+                note = Some(format!(
+                    "TODO `{}` is a range, the lower bound was used instead of {}",
+                    line.trim(),
+                    upper.as_str()
+                ));
+            }
+            (
+                Some(captures[1].split_whitespace().collect::<Vec<_>>().join(" ")),
+                captures[3].to_string(),
+            )
+        }
+        None => (None, line.clone()),
+    };
+    let words: Vec<&str> = rest.split_whitespace().collect();
+    let mut unit = None;
+    let mut name_start = 0;
+    if quantity.is_some() {
+        for len in [2, 1] {
+            if words.len() > len {
+                let candidate = words[..len].join(" ");
+                let candidate = candidate.trim_end_matches('.');
+                if is_known_unit(candidate) {
+                    unit = Some(candidate.to_string());
+                    name_start = len;
+                    break;
+                }
+            }
+        }
+    }
+    let mut name = words[name_start..].join(" ");
+    if let Some(stripped) = name.strip_prefix("of ") {
+        name = stripped.to_string();
+    }
+    let name = sanitize(&name);
+    let ingredient = match edit::ingredient(quantity.as_deref(), unit.as_deref(), &name) {
+        Ok(ingredient) => ingredient,
+        Err(_) => {
+            note = Some(format!("TODO check the quantity of `{}`", line.trim()));
+            edit::ingredient(None, None, &sanitize(&line)).expect("ingredient without quantity")
+        }
+    };
+    (ingredient, note)
+}
+
+/// Make text valid as a single sentence of slop source, replacing the characters that are
+/// syntax.
+pub(crate) fn sanitize(text: &str) -> String {
+    let text: String = text
+        .chars()
+        .map(|c| match c {
+            ':' => ',',
+            '*' | '#' | '=' | '>' => ' ',
+            c => c,
+        })
+        .collect();
+    let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    match text.chars().next() {
+        None => "untitled".to_string(),
+        Some(first) if first.is_numeric() || matches!(first, '<' | '^') => format!("- {text}"),
+        Some(_) => text,
+    }
+}
@@ -0,0 +1,145 @@
+use once_cell::sync::Lazy;
+use regex::{Captures, Regex};
+
+use super::{sanitize, to_source, Draft, DraftStep, Error};
+use crate::edit;
+
+// Import of Cooklang recipes.
+//
+// Each paragraph of a Cooklang file is a step, ingredients are marked up in the step text as
+// `@name{quantity%unit}`, cookware as `#name{}` and timers as `~{quantity%unit}`. The markup is
+// replaced by plain text and the ingredients are used by the step they appear in, so the steps
+// form a chain in the order they are written. Metadata such as `>> servings: 4` fills in the
+// title, description and yield, other metadata is dropped. Notes written as `> text` join the
+// description.
+
+// `@multi word name{...}`, `@name{...}` or `@name`, optionally followed by a preparation in
+// parentheses. The name of a multi word item ends at the braces.
+static MARKUP: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"([@#~])(?:([^@#~{}\n.,;:!?]*?)\{([^}]*)\}|([\w-]+))(?:\(([^)]*)\))?").unwrap()
+});
+static BLOCK_COMMENT: Lazy<Regex> = Lazy::new(|| Regex::new(r"(?s)\[-.*?-\]").unwrap());
+
+/// Convert a Cooklang recipe into formatted slop source.
+pub fn import(cooklang: &str) -> Result<String, Error> {
+    to_source(vec![draft(cooklang).into_recipe()])
+}
+
+fn draft(cooklang: &str) -> Draft {
+    let text = BLOCK_COMMENT.replace_all(cooklang, "");
+    let mut lines = text.lines().peekable();
+    let mut metadata = Vec::new();
+    // YAML front matter, only flat `key: value` pairs are read.
+    if lines.peek().map(|line| line.trim()) == Some("---") {
+        lines.next();
+        for line in lines.by_ref() {
+            if line.trim() == "---" {
+                break;
+            }
+            metadata.extend(line.split_once(':'));
+        }
+    }
+
+    let mut draft = Draft::default();
+    let mut notes = Vec::new();
+    let mut paragraph: Vec<&str> = Vec::new();
+    for line in lines {
+        let line = match line.find("--") {
+            Some(comment) => &line[..comment],
+            None => line,
+        };
+        let line = line.trim();
+        if let Some(entry) = line.strip_prefix(">>") {
+            metadata.extend(entry.split_once(':'));
+        } else if let Some(note) = line.strip_prefix('>') {
+            notes.push(note.trim().to_string());
+        } else if line.is_empty() || line.starts_with('=') {
+            // Blank lines and section headings end a step.
+            draft
+                .steps
+                .extend(step(&paragraph.join(" "), &mut draft.notes));
+            paragraph.clear();
+        } else {
+            paragraph.push(line);
+        }
+    }
+    draft
+        .steps
+        .extend(step(&paragraph.join(" "), &mut draft.notes));
+
+    let mut description = Vec::new();
+    for (key, value) in metadata {
+        let value = value.trim().trim_matches('"').to_string();
+        match key.trim().to_lowercase().as_str() {
+            "title" => draft.title = Some(value),
+            "description" => description.push(value),
+            "servings" | "serves" | "yield" => draft.recipe_yield = Some(value),
+            _ => {}
+        }
+    }
+    description.append(&mut notes);
+    draft.description = (!description.is_empty()).then(|| description.join(" "));
+    draft
+}
+
+// Replace the markup of a step by plain text and collect its ingredients.
+fn step(text: &str, notes: &mut Vec<String>) -> Option<DraftStep> {
+    let text = text.trim();
+    if text.is_empty() {
+        return None;
+    }
+    let mut ingredients = Vec::new();
+    let text = MARKUP.replace_all(text, |captures: &Captures| {
+        let name = captures
+            .get(2)
+            .or(captures.get(4))
+            .map_or("", |m| m.as_str().trim());
+        let (quantity, unit) = match captures.get(3).map(|m| m.as_str()) {
+            Some(amount) => {
+                let (quantity, unit) = amount.split_once('%').unwrap_or((amount, ""));
+                // `*` marks quantities that don't scale, `=` is the newer syntax for it.
+                let quantity = quantity
+                    .trim()
+                    .trim_end_matches('*')
+                    .trim_start_matches('=');
+                let non_empty =
+                    |text: &str| (!text.trim().is_empty()).then(|| text.trim().to_string());
+                let quantity = non_empty(quantity);
+                let unit = quantity.as_ref().and(non_empty(unit));
+                (quantity, unit)
+            }
+            None => (None, None),
+        };
+        let mut replacement = match &captures[1] {
+            "@" => {
+                let name = sanitize(name.trim_start_matches('&'));
+                let ingredient = edit::ingredient(quantity.as_deref(), unit.as_deref(), &name)
+                    .unwrap_or_else(|_| {
+                        notes.push(format!(
+                            "TODO `{}` is not a quantity, check the amount of {name}",
+                            captures[3].trim()
+                        ));
+                        edit::ingredient(None, None, &name).expect("ingredient without quantity")
+                    });
+                ingredients.push(ingredient);
+                name
+            }
+            "#" => name.to_string(),
+            // Timers are written out so the duration is picked up from the step text.
+            _ if quantity.is_some() => [quantity, unit]
+                .into_iter()
+                .flatten()
+                .collect::<Vec<_>>()
+                .join(" "),
+            _ => name.to_string(),
+        };
+        if let Some(preparation) = captures.get(5) {
+            replacement += &format!(" ({})", preparation.as_str().trim());
+        }
+        replacement
+    });
+    Some(DraftStep {
+        text: text.split_whitespace().collect::<Vec<_>>().join(" "),
+        ingredients,
+    })
+}
@@ -0,0 +1,157 @@
+use super::{parse_ingredient, Draft, DraftStep, Error, Imported};
+
+// Import of MealMaster text files.
+//
+// A file holds any number of recipes, each between a `MMMMM----- Recipe via Meal-Master` or
+// `----- Recipe via Meal-Master` line and a closing `MMMMM` or `-----` line. A recipe starts with
+// `Title:`, `Categories:` and `Yield:` or `Servings:` fields followed by the ingredients in
+// columns: the quantity in the first seven characters, a two letter unit code and the name from
+// the eleventh character. Names starting with `-` continue the ingredient above. Everything after
+// the ingredients is directions, one step per paragraph.
+
+// Units codes of the MealMaster format, sizes are kept as part of the name.
+#[rustfmt::skip]
+const UNITS: &[(&str, &str)] = &[
+    ("x", ""), ("ea", ""), ("sm", "small"), ("md", "medium"), ("lg", "large"),
+    ("cn", "can"), ("pk", "package"), ("pn", "pinch"), ("dr", "drop"), ("ds", "dash"),
+    ("ct", "carton"), ("bn", "bunch"), ("sl", "slice"), ("t", "tsp"), ("ts", "tsp"),
+    ("T", "tbsp"), ("tb", "tbsp"), ("fl", "fl oz"), ("c", "cup"), ("pt", "pint"),
+    ("qt", "quart"), ("ga", "gallon"), ("oz", "oz"), ("lb", "lb"), ("ml", "ml"), ("cb", "cc"),
+    ("cl", "cl"), ("dl", "dl"), ("l", "l"), ("mg", "mg"), ("cg", "cg"), ("dg", "dg"), ("g", "g"),
+    ("kg", "kg"),
+];
+
+/// Convert the recipes of a MealMaster file, one result per recipe.
+pub fn import(text: &str) -> Result<Vec<Imported>, Error> {
+    let mut recipes = Vec::new();
+    let mut lines: Option<Vec<&str>> = None;
+    for line in text.lines() {
+        let trimmed = line.trim();
+        let is_start = (trimmed.starts_with("MMMMM") || trimmed.starts_with("-----"))
+            && trimmed.to_lowercase().contains("meal-master");
+        if is_start {
+            // A recipe without a closing line ends at the next one.
+            recipes.extend(
+                lines
+                    .replace(Vec::new())
+                    .map(|lines| recipe(&lines, recipes.len())),
+            );
+        } else if matches!(trimmed, "MMMMM" | "-----") {
+            recipes.extend(lines.take().map(|lines| recipe(&lines, recipes.len())));
+        } else if let Some(lines) = &mut lines {
+            lines.push(line);
+        }
+    }
+    recipes.extend(lines.map(|lines| recipe(&lines, recipes.len())));
+    if recipes.is_empty() {
+        return Err(Error::NoRecipe);
+    }
+    Ok(recipes)
+}
+
+fn recipe(lines: &[&str], index: usize) -> Imported {
+    let mut draft = Draft::default();
+    let mut ingredients: Vec<String> = Vec::new();
+    let mut paragraph: Vec<&str> = Vec::new();
+    let mut directions = false;
+    for line in lines {
+        let trimmed = line.trim();
+        if !directions {
+            if let Some((field, value)) = trimmed.split_once(':') {
+                let value = Some(value.trim().to_string()).filter(|value| !value.is_empty());
+                match field.trim().to_lowercase().as_str() {
+                    "title" => {
+                        draft.title = value;
+                        continue;
+                    }
+                    "categories" => continue,
+                    "yield" | "servings" => {
+                        draft.recipe_yield = value;
+                        continue;
+                    }
+                    _ => {}
+                }
+            }
+            // Headings of ingredient groups, e.g. `MMMMM-----BATTER-----`.
+            if trimmed.is_empty() || trimmed.starts_with("MMMMM") || trimmed.starts_with("---") {
+                continue;
+            }
+            let columns = ingredient_columns(line);
+            if !columns.is_empty() {
+                for (quantity, unit, name) in columns {
+                    match (name.strip_prefix('-'), ingredients.last_mut()) {
+                        (Some(rest), Some(last)) if quantity.is_empty() => {
+                            *last += &format!(" {}", rest.trim());
+                        }
+                        _ => ingredients.push(format!("{quantity} {unit} {name}")),
+                    }
+                }
+                continue;
+            }
+            directions = true;
+        }
+        if trimmed.is_empty() {
+            draft.steps.extend(step(&paragraph));
+            paragraph.clear();
+        } else {
+            paragraph.push(trimmed);
+        }
+    }
+    draft.steps.extend(step(&paragraph));
+    for line in ingredients {
+        let (ingredient, note) = parse_ingredient(&line.replace(';', ","));
+        draft.ingredients.push(ingredient);
+        draft.notes.extend(note);
+    }
+    let name = draft
+        .title
+        .clone()
+        .unwrap_or_else(|| format!("recipe {}", index + 1));
+    Imported::new(name, Ok(draft))
+}
+
+fn step(paragraph: &[&str]) -> Option<DraftStep> {
+    (!paragraph.is_empty()).then(|| DraftStep {
+        text: paragraph.join(" "),
+        ingredients: Vec::new(),
+    })
+}
+
+// The quantity, unit and name of the ingredients of a line, ingredients can be written in two
+// columns with the second starting at the 42nd character.
+fn ingredient_columns(line: &str) -> Vec<(String, String, String)> {
+    let chars: Vec<char> = line.trim_end().chars().collect();
+    let (first, second) = if chars.len() > 52 && ingredient(&chars[41..]).is_some() {
+        (&chars[..41], Some(&chars[41..]))
+    } else {
+        (&chars[..], None)
+    };
+    match ingredient(first) {
+        Some(first) => std::iter::once(first)
+            .chain(second.and_then(ingredient))
+            .collect(),
+        None => Vec::new(),
+    }
+}
+
+fn ingredient(chars: &[char]) -> Option<(String, String, String)> {
+    if chars.len() < 12 || chars[7] != ' ' || chars[10] != ' ' {
+        return None;
+    }
+    let quantity: String = chars[..7].iter().collect();
+    if !quantity
+        .chars()
+        .all(|c| c.is_ascii_digit() || matches!(c, ' ' | '/' | '.' | '-'))
+    {
+        return None;
+    }
+    let code: String = chars[8..10].iter().collect();
+    let unit = UNITS
+        .iter()
+        .find(|(known, _)| *known == code.trim())
+        .map(|(_, unit)| unit.to_string())
+        .or_else(|| code.trim().is_empty().then(String::new))?;
+    let name: String = chars[11..].iter().collect();
+    let name = name.trim();
+    (!name.is_empty()).then(|| (quantity.trim().to_string(), unit, name.to_string()))
+}
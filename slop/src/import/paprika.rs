@@ -0,0 +1,98 @@
+use std::io::{Cursor, Read};
+
+use flate2::read::GzDecoder;
+use serde_json::Value;
+
+use super::{parse_ingredient, Draft, DraftStep, Error, Imported};
+
+// Import of Paprika recipe exports.
+//
+// A `.paprikarecipes` archive is a zip of `.paprikarecipe` entries, each a gzipped JSON object.
+// Ingredients and directions are plain text with one item per line. A single gzipped
+// `.paprikarecipe` is read as an archive of one recipe.
+
+/// Convert the recipes of a Paprika archive, one result per recipe.
+pub fn import(archive: &[u8]) -> Result<Vec<Imported>, Error> {
+    if archive.starts_with(&[0x1f, 0x8b]) {
+        return Ok(vec![entry("recipe".to_string(), archive)]);
+    }
+    let mut zip = zip::ZipArchive::new(Cursor::new(archive))?;
+    let mut recipes = Vec::new();
+    for i in 0..zip.len() {
+        let mut file = zip.by_index(i)?;
+        if file.is_dir() {
+            continue;
+        }
+        let name = file
+            .name()
+            .trim_end_matches(".paprikarecipe")
+            .rsplit('/')
+            .next()
+            .unwrap_or_default()
+            .to_string();
+        let mut bytes = Vec::new();
+        file.read_to_end(&mut bytes)?;
+        recipes.push(entry(name, &bytes));
+    }
+    if recipes.is_empty() {
+        return Err(Error::NoRecipe);
+    }
+    Ok(recipes)
+}
+
+fn entry(name: String, gzipped: &[u8]) -> Imported {
+    let recipe = read(gzipped);
+    let name = match &recipe {
+        Ok(recipe) => text(recipe.get("name")).unwrap_or(name),
+        Err(_) => name,
+    };
+    Imported::new(name, recipe.map(|recipe| draft(&recipe)))
+}
+
+fn read(gzipped: &[u8]) -> Result<Value, Error> {
+    let mut json = String::new();
+    GzDecoder::new(gzipped).read_to_string(&mut json)?;
+    Ok(serde_json::from_str(&json)?)
+}
+
+fn draft(recipe: &Value) -> Draft {
+    let description: Vec<String> = [recipe.get("description"), recipe.get("notes")]
+        .into_iter()
+        .filter_map(text)
+        .collect();
+    let mut draft = Draft {
+        title: text(recipe.get("name")),
+        description: (!description.is_empty()).then(|| description.join(" ")),
+        recipe_yield: text(recipe.get("servings")),
+        ..Draft::default()
+    };
+    for line in lines(recipe.get("ingredients")) {
+        let (ingredient, note) = parse_ingredient(&line);
+        draft.ingredients.push(ingredient);
+        draft.notes.extend(note);
+    }
+    draft.steps = lines(recipe.get("directions"))
+        .into_iter()
+        .map(|text| DraftStep {
+            text,
+            ingredients: Vec::new(),
+        })
+        .collect();
+    draft
+}
+
+fn text(value: Option<&Value>) -> Option<String> {
+    let text = value?.as_str()?.trim();
+    (!text.is_empty()).then(|| text.split_whitespace().collect::<Vec<_>>().join(" "))
+}
+
+fn lines(value: Option<&Value>) -> Vec<String> {
+    value
+        .and_then(Value::as_str)
+        .unwrap_or_default()
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(str::to_string)
+        .collect()
+}
@@ -0,0 +1,117 @@
+use serde_json::Value;
+
+use super::{parse_ingredient, to_source, Draft, DraftStep, Error};
+
+// Import of schema.org `Recipe` objects, as embedded in web pages as JSON-LD.
+//
+// Recipes are found anywhere in the document, including in a `@graph` or in a list of objects.
+// `recipeIngredient` strings are parsed into quantity, unit and name. `recipeInstructions` can be
+// a single string, a list of strings, or `HowToStep` and `HowToSection` objects; sections are
+// flattened into their steps.
+
+/// Convert the schema.org recipes of a JSON-LD document into formatted slop source.
+pub fn import(json: &str) -> Result<String, Error> {
+    let value: Value = serde_json::from_str(json)?;
+    let mut recipes = Vec::new();
+    find_recipes(&value, &mut recipes);
+    to_source(
+        recipes
+            .into_iter()
+            .map(|recipe| draft(recipe).into_recipe())
+            .collect(),
+    )
+}
+
+fn find_recipes<'a>(value: &'a Value, recipes: &mut Vec<&'a Value>) {
+    match value {
+        Value::Array(values) => values.iter().for_each(|v| find_recipes(v, recipes)),
+        Value::Object(object) => {
+            if has_type(value, "Recipe") {
+                recipes.push(value);
+            } else if let Some(graph) = object.get("@graph") {
+                find_recipes(graph, recipes);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn has_type(value: &Value, name: &str) -> bool {
+    match value.get("@type") {
+        Some(Value::String(t)) => t == name,
+        Some(Value::Array(types)) => types.iter().any(|t| t.as_str() == Some(name)),
+        _ => false,
+    }
+}
+
+fn draft(recipe: &Value) -> Draft {
+    let mut draft = Draft {
+        title: text(recipe.get("name")),
+        description: text(recipe.get("description")),
+        // Yields are often given as a count and as text, e.g. `["4", "4 servings"]`.
+        recipe_yield: strings(recipe.get("recipeYield"))
+            .into_iter()
+            .max_by_key(String::len),
+        ..Draft::default()
+    };
+    for line in strings(recipe.get("recipeIngredient")) {
+        let (ingredient, note) = parse_ingredient(&line);
+        draft.ingredients.push(ingredient);
+        draft.notes.extend(note);
+    }
+    let mut steps = Vec::new();
+    instructions(recipe.get("recipeInstructions"), &mut steps);
+    draft.steps = steps
+        .into_iter()
+        .map(|text| DraftStep {
+            text,
+            ingredients: Vec::new(),
+        })
+        .collect();
+    draft
+}
+
+fn instructions(value: Option<&Value>, steps: &mut Vec<String>) {
+    match value {
+        // A single string holds one step per line.
+        Some(Value::String(text)) => steps.extend(
+            text.lines()
+                .map(str::trim)
+                .filter(|line| !line.is_empty())
+                .map(str::to_string),
+        ),
+        Some(Value::Array(values)) => values.iter().for_each(|v| instructions(Some(v), steps)),
+        Some(value @ Value::Object(_)) if has_type(value, "HowToSection") => {
+            instructions(value.get("itemListElement"), steps)
+        }
+        Some(value @ Value::Object(_)) => {
+            steps.extend(text(value.get("text")).or_else(|| text(value.get("name"))))
+        }
+        _ => {}
+    }
+}
+
+// The first string of a value that is a string, a number or a list of them.
+fn text(value: Option<&Value>) -> Option<String> {
+    strings(value).into_iter().next()
+}
+
+fn strings(value: Option<&Value>) -> Vec<String> {
+    match value {
+        Some(Value::String(s)) if !s.trim().is_empty() => vec![unescape(s.trim())],
+        Some(Value::Number(n)) => vec![n.to_string()],
+        Some(Value::Array(values)) => values.iter().flat_map(|v| strings(Some(v))).collect(),
+        _ => Vec::new(),
+    }
+}
+
+// Pages often embed HTML entities in the JSON.
+fn unescape(text: &str) -> String {
+    text.replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&apos;", "'")
+        .replace("&nbsp;", " ")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&amp;", "&")
+}
@@ -0,0 +1,264 @@
+use super::{parse_ingredient, to_source, Draft, DraftStep, Error};
+use crate::{menu::normalize_ingredient, parser::lex_quantity, semantic::Ingredient};
+
+// Conversion of recipes written as plain text, e.g. copied from a web page or an email.
+//
+// Ingredient lines are found with the quantity rules of slop source: a line starting with a
+// number or fraction is an ingredient, as is any line under an `Ingredients` heading. The lines
+// before the first ingredient are the title, the description and a yield such as `Serves 4`, the
+// lines after the last one are the directions. When the text has headings the intro lasts until
+// the first of them, and a first line followed by more intro text is the title even when it
+// starts with a number, e.g. `7 Layer Dip`. Each sentence of the directions is a step, and an
+// ingredient joins the first step that mentions it, by its whole name or else by its last word,
+// e.g. `flour` for `all-purpose flour`. Matches by last word, ingredients no step mentions and
+// steps done in another bowl, which are still applied to the steps before, are flagged with TODO
+// notes. The notes number the directions as the author did, by numbered line or paragraph.
+
+// Headings of the ingredient and direction sections.
+const INGREDIENTS: &[&str] = &["ingredients"];
+const DIRECTIONS: &[&str] = &[
+    "directions",
+    "instructions",
+    "method",
+    "preparation",
+    "steps",
+];
+
+#[derive(Clone, Copy, PartialEq)]
+enum Section {
+    Intro,
+    Ingredients,
+    Directions,
+}
+
+/// Convert a plain text recipe into formatted slop source.
+pub fn import(text: &str) -> Result<String, Error> {
+    let mut intro: Vec<&str> = Vec::new();
+    let mut ingredients: Vec<&str> = Vec::new();
+    let mut directions: Vec<String> = Vec::new();
+    let mut paragraph: Vec<&str> = Vec::new();
+    let mut recipe_yield = None;
+    let mut section = Section::Intro;
+    let lines: Vec<&str> = text.lines().map(|line| strip_bullet(line.trim())).collect();
+    // Whether the sections are marked by headings rather than guessed.
+    let headings = lines.iter().any(|line| heading(line).is_some());
+    let title = title_line(&lines);
+    for (index, &line) in lines.iter().enumerate() {
+        if let Some(heading) = heading(line) {
+            section = heading;
+            continue;
+        }
+        let is_ingredient = is_ingredient(line) && Some(index) != title;
+        section = match section {
+            Section::Intro if is_ingredient && !headings => Section::Ingredients,
+            Section::Ingredients if !headings && !is_ingredient && !line.is_empty() => {
+                Section::Directions
+            }
+            section => section,
+        };
+        match section {
+            _ if line.is_empty() => {
+                directions.extend((!paragraph.is_empty()).then(|| paragraph.join(" ")));
+                paragraph.clear();
+            }
+            Section::Intro => match yield_line(line) {
+                Some(line) => recipe_yield = Some(line),
+                None => intro.push(line),
+            },
+            Section::Ingredients => ingredients.push(line),
+            Section::Directions => {
+                // A numbered line starts a new direction even without a blank line before it.
+                let text = strip_number(line);
+                if text.len() != line.len() && !paragraph.is_empty() {
+                    directions.push(paragraph.join(" "));
+                    paragraph.clear();
+                }
+                paragraph.push(text);
+            }
+        }
+    }
+    directions.extend((!paragraph.is_empty()).then(|| paragraph.join(" ")));
+    if ingredients.is_empty() && directions.is_empty() {
+        return Err(Error::NoRecipe);
+    }
+
+    let mut draft = Draft {
+        title: intro.first().map(|title| title.to_string()),
+        description: (intro.len() > 1).then(|| intro[1..].join(" ")),
+        recipe_yield,
+        ..Draft::default()
+    };
+    // The number of the direction each step is a sentence of, as the author numbered them.
+    let mut numbers = Vec::new();
+    for (number, paragraph) in directions.iter().enumerate() {
+        for text in sentences(paragraph) {
+            if !draft.steps.is_empty() && is_separate(&text) {
+                draft.notes.push(format!(
+                    "TODO step {} was applied to the result of the step before although it \
+                     says \"{}\"",
+                    number + 1,
+                    text.trim_end_matches('.')
+                ));
+            }
+            numbers.push(number + 1);
+            draft.steps.push(DraftStep {
+                text,
+                ingredients: Vec::new(),
+            });
+        }
+    }
+    let mut unmentioned = Vec::new();
+    for line in ingredients {
+        let (ingredient, note) = parse_ingredient(line);
+        draft.notes.extend(note);
+        match first_mention(&ingredient, &draft.steps) {
+            Some((step, None)) => draft.steps[step].ingredients.push(ingredient),
+            Some((step, Some(word))) => {
+                draft.notes.push(format!(
+                    "TODO {} was guessed to be added in step {} as it mentions {word}",
+                    ingredient.text(),
+                    numbers[step]
+                ));
+                draft.steps[step].ingredients.push(ingredient);
+            }
+            None => unmentioned.push(ingredient),
+        }
+    }
+    if !unmentioned.is_empty() && !draft.steps.is_empty() {
+        let names: Vec<&str> = unmentioned.iter().map(Ingredient::text).collect();
+        draft.notes.push(format!(
+            "TODO no step mentions {}, added in the first step",
+            names.join(", ")
+        ));
+        let first = &mut draft.steps[0].ingredients;
+        unmentioned.append(first);
+        *first = unmentioned;
+    } else {
+        draft.ingredients = unmentioned;
+    }
+    to_source(vec![draft.into_recipe()])
+}
+
+// The section started by a heading line such as `Ingredients:`.
+fn heading(line: &str) -> Option<Section> {
+    let heading = line.trim_end_matches(':').trim().to_lowercase();
+    if INGREDIENTS.contains(&heading.as_str()) {
+        Some(Section::Ingredients)
+    } else if DIRECTIONS.contains(&heading.as_str()) {
+        Some(Section::Directions)
+    } else {
+        None
+    }
+}
+
+// The index of the first line when it is the title rather than an ingredient, i.e. when more
+// intro text follows it before the next ingredient.
+fn title_line(lines: &[&str]) -> Option<usize> {
+    let first = lines.iter().position(|line| !line.is_empty())?;
+    let mut rest = lines[first + 1..]
+        .iter()
+        .filter(|line| !line.is_empty() && heading(line).is_none());
+    let intro = rest.next().is_some_and(|line| !is_ingredient(line));
+    (intro && rest.any(|line| is_ingredient(line))).then_some(first)
+}
+
+// The yield of lines such as `Serves 4` or `Yield: 1 loaf`.
+fn yield_line(line: &str) -> Option<String> {
+    let (keyword, rest) = line.split_once([' ', ':'])?;
+    let rest = rest.trim_start_matches(':').trim();
+    match keyword.to_lowercase().as_str() {
+        "serves" | "servings" | "makes" | "yield" | "yields" if !rest.is_empty() => {
+            Some(rest.to_string())
+        }
+        _ => None,
+    }
+}
+
+fn strip_bullet(line: &str) -> &str {
+    line.strip_prefix(['-', '*', '•'])
+        .map_or(line, |rest| rest.trim_start())
+}
+
+// Remove step numbers like `1.`, `2)` or `Step 3:`.
+fn strip_number(line: &str) -> &str {
+    let rest = line
+        .strip_prefix("Step ")
+        .or_else(|| line.strip_prefix("step "))
+        .unwrap_or(line);
+    let number = rest.trim_start_matches(|c: char| c.is_ascii_digit());
+    if number.len() == rest.len() {
+        return line;
+    }
+    match number.strip_prefix(['.', ')', ':']) {
+        Some(rest) => rest.trim_start(),
+        // `Step 2 Mix` has no punctuation after the number.
+        None if rest.len() != line.len() => number.trim_start(),
+        None => line,
+    }
+}
+
+// Whether the line starts with a quantity, rather than with a step number like `1.` or `2)`.
+fn is_ingredient(line: &str) -> bool {
+    lex_quantity(line)
+        .is_some_and(|(quantity, rest)| !quantity.ends_with('.') && !rest.starts_with([')', ':']))
+}
+
+// Split a paragraph after the punctuation ending a sentence when the next one starts with a
+// capital letter, so abbreviations such as `approx. 5 minutes` stay together.
+fn sentences(paragraph: &str) -> Vec<String> {
+    let mut sentences = Vec::new();
+    let mut start = 0;
+    let chars: Vec<(usize, char)> = paragraph.char_indices().collect();
+    for window in chars.windows(3) {
+        let [(_, end), (_, space), (next, capital)] = window else {
+            continue;
+        };
+        if matches!(end, '.' | '!' | '?') && space.is_whitespace() && capital.is_uppercase() {
+            sentences.push(paragraph[start..*next].trim().to_string());
+            start = *next;
+        }
+    }
+    sentences.push(paragraph[start..].trim().to_string());
+    sentences.retain(|sentence| !sentence.is_empty());
+    sentences
+}
+
+// Whether the sentence is done apart from the steps before it, e.g. `Whisk the flour in another
+// bowl`.
+fn is_separate(sentence: &str) -> bool {
+    let sentence = sentence.to_lowercase();
+    ["another bowl", "separate bowl", "separately"]
+        .iter()
+        .any(|phrase| sentence.contains(phrase))
+}
+
+// The first step mentioning the ingredient, with the word it was matched by when it isn't
+// mentioned by its whole name.
+fn first_mention(ingredient: &Ingredient, steps: &[DraftStep]) -> Option<(usize, Option<String>)> {
+    // Notes after the name, e.g. `butter, softened`, aren't part of it.
+    let name = ingredient
+        .text()
+        .split([',', '('])
+        .next()
+        .unwrap_or_default();
+    let name = words(name);
+    let steps: Vec<Vec<String>> = steps.iter().map(|step| words(&step.text)).collect();
+    let mentions = |needle: &[String]| {
+        steps.iter().position(|words| {
+            !needle.is_empty() && words.windows(needle.len()).any(|window| window == needle)
+        })
+    };
+    if let Some(step) = mentions(&name) {
+        return Some((step, None));
+    }
+    let last = name.last()?;
+    mentions(std::slice::from_ref(last)).map(|step| (step, Some(last.clone())))
+}
+
+// Lowercase singular words, e.g. `Eggs,` becomes `egg`.
+fn words(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric() && c != '-' && c != '\'')
+        .filter(|word| !word.is_empty())
+        .map(normalize_ingredient)
+        .collect()
+}
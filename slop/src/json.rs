@@ -0,0 +1,204 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    ast::Position,
+    menu::aggregate_ingredients,
+    semantic::{self, Operand},
+};
+
+// JSON interchange format for compiled recipes.
+//
+// The format is versioned independently of the crate, see [`VERSION`]. Fields are only ever added
+// within a version, a change to the meaning or shape of an existing field bumps the version.
+// The JSON Schema for the format is published as [`SCHEMA`].
+
+/// Version of the JSON format produced by [`to_document`].
+pub const VERSION: u32 = 1;
+
+/// JSON Schema describing the [`Document`] format.
+pub const SCHEMA: &str = include_str!("../schema/recipe.v1.schema.json");
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct Document {
+    pub version: u32,
+    pub recipes: Vec<Recipe>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct Recipe {
+    pub span: Span,
+    pub title: Option<String>,
+    pub preamble: Option<String>,
+    pub comment: Option<String>,
+    #[serde(rename = "yield")]
+    pub recipe_yield: Option<String>,
+    /// Ingredients in source order.
+    pub ingredients: Vec<Ingredient>,
+    /// Total amounts of each ingredient, derived ingredients are not included.
+    pub totals: Vec<Total>,
+    pub tree: Node,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct Ingredient {
+    pub span: Span,
+    pub name: String,
+    pub derived: bool,
+    pub quantity: Option<Quantity>,
+    pub unit: Option<String>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct Quantity {
+    /// Quantity as written in the source, e.g. `1 1/2`.
+    pub text: String,
+    pub value: f64,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct Total {
+    pub name: String,
+    pub volume_liters: Option<f64>,
+    pub mass_kilograms: Option<f64>,
+    /// Amounts whose units could not be converted into a volume or mass.
+    pub other: Vec<Amount>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct Amount {
+    pub unit: String,
+    pub value: f64,
+}
+
+/// Node of the recipe tree.
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+#[non_exhaustive]
+pub enum Node {
+    /// Leaf of the tree, `index` refers to [`Recipe::ingredients`].
+    Ingredient {
+        span: Span,
+        index: usize,
+    },
+    Operator {
+        span: Span,
+        text: String,
+        operands: Vec<Node>,
+    },
+    Missing {
+        span: Span,
+    },
+    Unused {
+        span: Span,
+        operands: Vec<Node>,
+    },
+}
+
+/// Byte offsets into the source, `end` is exclusive.
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+}
+
+impl From<&Position> for Span {
+    fn from(position: &Position) -> Self {
+        Self {
+            start: position.start,
+            end: position.end,
+        }
+    }
+}
+
+pub fn to_document(file: &semantic::SourceFile) -> Document {
+    Document {
+        version: VERSION,
+        recipes: file.recipes.iter().map(convert_recipe).collect(),
+    }
+}
+
+/// Serialize the file into the JSON interchange format.
+pub fn to_string(file: &semantic::SourceFile) -> String {
+    serde_json::to_string_pretty(&to_document(file)).expect("document should serialize to JSON")
+}
+
+fn convert_recipe(r: &semantic::Recipe) -> Recipe {
+    let mut ingredients = Vec::new();
+    let tree = convert_operand(&r.root, &mut ingredients);
+    let mut totals: Vec<Total> = aggregate_ingredients(std::iter::once(r.clone()))
+        .into_iter()
+        .map(|(name, amounts)| Total {
+            name,
+            volume_liters: amounts.volume_liters(),
+            mass_kilograms: amounts.mass_kilograms(),
+            other: amounts
+                .arbitrary()
+                .map(|a| Amount {
+                    unit: a.units.clone(),
+                    value: a.value,
+                })
+                .collect(),
+        })
+        .collect();
+    totals.sort_by(|a, b| a.name.cmp(&b.name));
+    Recipe {
+        span: (&r.position).into(),
+        title: r.title.clone(),
+        preamble: r.preamble.clone(),
+        comment: r.comment.clone(),
+        recipe_yield: r.recipe_yield().map(str::to_string),
+        ingredients,
+        totals,
+        tree,
+    }
+}
+
+fn convert_operand(op: &Operand, ingredients: &mut Vec<Ingredient>) -> Node {
+    match op {
+        Operand::Ingredient(ing) => {
+            ingredients.push(Ingredient {
+                span: (&ing.position).into(),
+                name: ing.text.clone(),
+                derived: ing.derived,
+                quantity: ing.quantities.as_ref().map(|(text, value)| Quantity {
+                    text: text.clone(),
+                    value: *value,
+                }),
+                unit: ing.unit.clone(),
+            });
+            Node::Ingredient {
+                span: (&ing.position).into(),
+                index: ingredients.len() - 1,
+            }
+        }
+        Operand::Operator {
+            position,
+            text,
+            operands,
+            ..
+        } => Node::Operator {
+            span: position.into(),
+            text: text.clone(),
+            operands: operands
+                .iter()
+                .map(|op| convert_operand(op, ingredients))
+                .collect(),
+        },
+        Operand::MissingOperand { position } => Node::Missing {
+            span: position.into(),
+        },
+        Operand::UnusedOperands { position, operands } => Node::Unused {
+            span: position.into(),
+            operands: operands
+                .iter()
+                .map(|op| convert_operand(op, ingredients))
+                .collect(),
+        },
+    }
+}
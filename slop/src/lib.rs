@@ -1,40 +1,129 @@
+//! Slop is a language for writing recipes as trees of ingredients and steps.
+//!
+//! Source is compiled with [`compile`] into a [`SourceFile`] of [`Recipe`]s. Each recipe is a
+//! tree of [`Operand`]s where the leaves are [`Ingredient`]s and the inner nodes are the steps
+//! that combine them.
+//!
+//! ```
+//! let (file, errors) = slop::compile("<** Toast\n*1 slice: bread =toast\n>");
+//! assert!(errors.0.is_empty());
+//! let recipe = &file.recipes()[0];
+//! assert_eq!(recipe.title(), Some("Toast"));
+//! assert_eq!(recipe.ingredients()[0].text(), "bread");
+//! assert_eq!(recipe.steps()[0].text(), Some("toast"));
+//! ```
+//!
+//! # Features
+//!
+//! * `serde` - Derive `Serialize` and `Deserialize` for the [`ast`] and [`semantic`] trees and
+//!   enable the versioned JSON interchange format in `json`, the schema.org JSON-LD export in
+//!   `schema_org` and the schema.org importer in `import::schema_org`.
+//! * `paprika` - Enable the importer of Paprika archives in `import::paprika`, implies `serde`.
+//! * `pdf` - Enable the PDF rendering of books in `book::pdf`.
+//!
+//! # Stability
+//!
+//! The crate follows semantic versioning for the items re-exported at the crate root, the
+//! [`annotation`], [`book`], [`config`], [`cooklang`], [`edit`], [`equipment`], [`fix`],
+//! [`graph`], [`html`], [`ical`], [`import`], [`lint`], [`markdown`], [`menu`], [`prep`],
+//! [`prose`], [`timeline`] and [`visit`] modules and the free functions in this module. Types that
+//! may grow are marked `#[non_exhaustive]`: new enum variants, struct fields and error variants
+//! can be added in minor releases, so match them with a wildcard arm and construct them using the
+//! functions in [`edit`]. Prefer the accessor methods over the public fields, the fields are kept
+//! public for compatibility.
+//!
+//! The [`ast`] and [`cst`] modules mirror the syntax of the language and change whenever the
+//! syntax does, their shape is not covered by these guarantees.
+
 // Local modules
+pub mod annotation;
 pub mod ast;
+pub mod book;
+pub mod config;
+pub mod cooklang;
+pub mod cst;
+pub mod diagnostic;
+pub mod edit;
+pub mod equipment;
+pub mod fix;
 mod format;
+pub mod graph;
+pub mod html;
+pub mod ical;
+pub mod import;
+#[cfg(feature = "serde")]
+pub mod json;
+pub mod lint;
+pub mod markdown;
 pub mod menu;
 mod parser;
+pub mod prep;
+pub mod prose;
 mod quant;
-mod semantic;
+#[cfg(feature = "serde")]
+pub mod schema_org;
+pub mod semantic;
 mod svg;
+pub mod timeline;
+pub mod visit;
 
+pub use diagnostic::{Diagnostic, Severity};
 pub use parser::Error as ParseError;
+pub use quant::{Amounts, Arbitrary};
 pub use semantic::Error as CompilationError;
+pub use semantic::{Ingredient, Operand, Recipe, SourceFile};
 
 use std::{fmt::Display, vec::IntoIter};
 
 use thiserror::Error;
 
 #[derive(Error, Debug)]
+#[non_exhaustive]
 pub enum Error {
     #[error("parse error: {0}")]
     ParseError(#[from] ParseError),
-    #[error("compilation")]
+    #[error("compilation error: {0}")]
     CompilationError(#[from] CompilationError),
 }
 
+impl From<&Error> for Diagnostic {
+    fn from(error: &Error) -> Self {
+        match error {
+            Error::ParseError(error) => error.into(),
+            Error::CompilationError(error) => error.into(),
+        }
+    }
+}
+
+/// Diagnostics found while processing source.
 #[derive(Debug)]
-pub struct Errors(pub Vec<Error>);
+pub struct Errors(pub Vec<Diagnostic>);
+
+impl Errors {
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+    pub fn iter(&self) -> std::slice::Iter<'_, Diagnostic> {
+        self.0.iter()
+    }
+}
 
 impl From<Vec<Error>> for Errors {
     fn from(value: Vec<Error>) -> Self {
+        Self(value.iter().map(Diagnostic::from).collect())
+    }
+}
+
+impl From<Vec<Diagnostic>> for Errors {
+    fn from(value: Vec<Diagnostic>) -> Self {
         Self(value)
     }
 }
 
 impl IntoIterator for Errors {
-    type Item = Error;
+    type Item = Diagnostic;
 
-    type IntoIter = IntoIter<Error>;
+    type IntoIter = IntoIter<Diagnostic>;
 
     fn into_iter(self) -> Self::IntoIter {
         self.0.into_iter()
@@ -47,7 +136,7 @@ impl Display for Errors {
         if self.0.is_empty() {
             return Ok(());
         }
-        write!(f, "Found {} errors:", self.0.len())?;
+        writeln!(f, "Found {} errors:", self.0.len())?;
         for error in &self.0 {
             writeln!(f, "{error}")?;
         }
@@ -66,6 +155,18 @@ pub fn parse(src: &str) -> (ast::SourceFile, Errors) {
             .into(),
     )
 }
+/// Parse source into a lossless concrete syntax tree, see [`cst`].
+pub fn parse_cst(src: &str) -> (cst::SyntaxNode, Errors) {
+    let (cst_file, errors) = cst::parse(src);
+    (
+        cst_file,
+        errors
+            .into_iter()
+            .map(Error::ParseError)
+            .collect::<Vec<Error>>()
+            .into(),
+    )
+}
 pub fn format(src: &str) -> (String, Errors) {
     let (src_ast, errors) = parse(src);
     (format::format(&src_ast), errors)
@@ -88,3 +189,15 @@ pub fn to_svgs(src: &str) -> (Vec<String>, Errors) {
     let (sem_file, errors) = compile(src);
     (svg::to_svgs(&sem_file), errors)
 }
+/// Render the timeline of every recipe as a Gantt chart, see [`timeline`].
+pub fn to_timeline_svgs(src: &str) -> (Vec<String>, Errors) {
+    let (sem_file, errors) = compile(src);
+    (
+        sem_file
+            .recipes
+            .iter()
+            .map(|r| svg::timeline_to_svg(r, &timeline::timeline(r)))
+            .collect(),
+        errors,
+    )
+}
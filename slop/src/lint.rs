@@ -0,0 +1,490 @@
+use std::collections::HashMap;
+
+use crate::{
+    ast,
+    diagnostic::{Code, Diagnostic, Severity},
+    quant::{canonical_unit, implausible_quantity, is_known_unit, suggest_unit},
+    semantic::{Ingredient, Operand, Recipe, SourceFile},
+    visit::{
+        self,
+        semantic::{walk_operand, Visit},
+    },
+};
+
+// Lints are checks over the semantic tree for recipes that compile but are likely mistakes. The
+// few mistakes the semantic tree no longer shows, such as a unary `+` merged into the operator
+// above it, are checked on the syntax tree.
+//
+// Each rule has a stable name used for configuration, a stable code used in diagnostics and
+// a default severity. The severity of each rule can be changed, or the rule disabled, with a
+// [`LintConfig`].
+
+pub trait Rule {
+    /// Name of the rule used in configuration, e.g. `unknown-unit`.
+    fn name(&self) -> &'static str;
+    fn code(&self) -> Code;
+    fn description(&self) -> &'static str;
+    fn default_severity(&self) -> Option<Severity> {
+        Some(Severity::Warning)
+    }
+    /// Check the recipe reporting any problems using the default severity of the rule.
+    fn check(&self, _recipe: &Recipe, _diagnostics: &mut Vec<Diagnostic>) {}
+    /// Check the syntax tree of the recipe, see [`Rule::check`].
+    fn check_syntax(&self, _recipe: &ast::Recipe, _diagnostics: &mut Vec<Diagnostic>) {}
+}
+
+/// Severity of each rule by name, `None` disables the rule.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct LintConfig {
+    pub rules: HashMap<String, Option<Severity>>,
+}
+
+impl LintConfig {
+    fn severity(&self, rule: &dyn Rule) -> Option<Severity> {
+        match self.rules.get(rule.name()) {
+            Some(severity) => *severity,
+            None => rule.default_severity(),
+        }
+    }
+}
+
+/// All available lint rules.
+pub fn rules() -> Vec<Box<dyn Rule>> {
+    vec![
+        Box::new(UnknownUnit),
+        Box::new(UnaryPlus),
+        Box::new(DuplicateIngredient),
+        Box::new(UnproducedDerived),
+        Box::new(EmptyOperatorText),
+        Box::new(MissingTitle),
+        Box::new(ZeroQuantity),
+        Box::new(NonCanonicalUnit),
+        Box::new(MixedDimensions),
+        Box::new(ImplausibleQuantity),
+    ]
+}
+
+/// Run all enabled rules over the file, `syntax` is the syntax tree it was compiled from.
+pub fn lint(syntax: &ast::SourceFile, file: &SourceFile, config: &LintConfig) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+    for rule in rules() {
+        let Some(severity) = config.severity(rule.as_ref()) else {
+            continue;
+        };
+        let mut found = Vec::new();
+        for recipe in &syntax.recipes {
+            rule.check_syntax(recipe, &mut found);
+        }
+        for recipe in &file.recipes {
+            rule.check(recipe, &mut found);
+        }
+        diagnostics.extend(found.into_iter().map(|mut d| {
+            d.severity = severity;
+            d
+        }));
+    }
+    diagnostics.sort_by_key(|d| (d.span().start, d.code));
+    diagnostics
+}
+
+/// Codes of the diagnostics produced by lint rules.
+pub mod codes {
+    use crate::diagnostic::Code;
+
+    pub const UNKNOWN_UNIT: Code = Code::new("L0001");
+    pub const UNARY_PLUS: Code = Code::new("L0002");
+    pub const DUPLICATE_INGREDIENT: Code = Code::new("L0003");
+    pub const UNPRODUCED_DERIVED: Code = Code::new("L0004");
+    pub const EMPTY_OPERATOR_TEXT: Code = Code::new("L0005");
+    pub const MISSING_TITLE: Code = Code::new("L0006");
+    pub const ZERO_QUANTITY: Code = Code::new("L0007");
+    pub const NON_CANONICAL_UNIT: Code = Code::new("L0008");
+    pub const MIXED_DIMENSIONS: Code = Code::new("L0009");
+    pub const IMPLAUSIBLE_QUANTITY: Code = Code::new("L0010");
+}
+
+// Collect ingredients and operators in source order.
+#[derive(Default)]
+struct Nodes<'a> {
+    ingredients: Vec<&'a Ingredient>,
+    operators: Vec<&'a Operand>,
+}
+
+impl<'a> Nodes<'a> {
+    fn of(recipe: &'a Recipe) -> Self {
+        let mut nodes = Self::default();
+        nodes.visit_operand(&recipe.root);
+        nodes
+    }
+}
+
+impl<'a> Visit<'a> for Nodes<'a> {
+    fn visit_operand(&mut self, op: &'a Operand) {
+        walk_operand(self, op);
+        if let Operand::Operator { .. } = op {
+            self.operators.push(op);
+        }
+    }
+    fn visit_ingredient(&mut self, ingredient: &'a Ingredient) {
+        self.ingredients.push(ingredient);
+    }
+}
+
+pub struct UnknownUnit;
+
+impl Rule for UnknownUnit {
+    fn name(&self) -> &'static str {
+        "unknown-unit"
+    }
+    fn code(&self) -> Code {
+        codes::UNKNOWN_UNIT
+    }
+    fn description(&self) -> &'static str {
+        "ingredient unit is not a recognized unit of measure"
+    }
+    fn check(&self, recipe: &Recipe, diagnostics: &mut Vec<Diagnostic>) {
+        for ing in Nodes::of(recipe).ingredients {
+            if let Some(unit) = &ing.unit {
+                if !is_known_unit(unit) {
+                    let mut diagnostic = Diagnostic::warning(
+                        self.code(),
+                        format!("unknown unit `{unit}`"),
+                        ing.position.clone(),
+                    )
+                    .with_primary_message("unit is not recognized");
+                    if let Some(suggestion) = suggest_unit(unit) {
+                        diagnostic = diagnostic.with_help(format!("did you mean `{suggestion}`?"));
+                    }
+                    diagnostics.push(diagnostic);
+                }
+            }
+        }
+    }
+}
+
+pub struct UnaryPlus;
+
+impl Rule for UnaryPlus {
+    fn name(&self) -> &'static str {
+        "unary-plus"
+    }
+    fn code(&self) -> Code {
+        codes::UNARY_PLUS
+    }
+    fn description(&self) -> &'static str {
+        "`+` combines operands and should only be used with the binary `#` operator"
+    }
+    // The semantic tree merges `+` operators into the operator above them, hiding `=+` under
+    // a binary operator such as `*a =+ *b #mix`.
+    fn check_syntax(&self, recipe: &ast::Recipe, diagnostics: &mut Vec<Diagnostic>) {
+        let mut finder = UnaryPlusFinder(Vec::new());
+        visit::ast::Visit::visit_recipe(&mut finder, recipe);
+        for position in finder.0 {
+            diagnostics.push(
+                Diagnostic::warning(
+                    self.code(),
+                    "`+` used as unary operator text",
+                    position.clone(),
+                )
+                .with_help("use `#+` to combine two operands"),
+            );
+        }
+    }
+}
+
+struct UnaryPlusFinder<'a>(Vec<&'a ast::Position>);
+
+impl<'a> visit::ast::Visit<'a> for UnaryPlusFinder<'a> {
+    fn visit_operand(&mut self, op: &'a ast::Operand) {
+        if let ast::Operand::UnaryOp { position, text, .. } = op {
+            if text == "+" {
+                self.0.push(position);
+            }
+        }
+        visit::ast::walk_operand(self, op)
+    }
+}
+
+pub struct DuplicateIngredient;
+
+impl Rule for DuplicateIngredient {
+    fn name(&self) -> &'static str {
+        "duplicate-ingredient"
+    }
+    fn code(&self) -> Code {
+        codes::DUPLICATE_INGREDIENT
+    }
+    fn description(&self) -> &'static str {
+        "the same ingredient is listed more than once"
+    }
+    fn default_severity(&self) -> Option<Severity> {
+        Some(Severity::Hint)
+    }
+    fn check(&self, recipe: &Recipe, diagnostics: &mut Vec<Diagnostic>) {
+        let mut seen: HashMap<String, &Ingredient> = HashMap::new();
+        for ing in Nodes::of(recipe).ingredients {
+            if ing.derived {
+                continue;
+            }
+            let name = ing.text.to_lowercase();
+            if let Some(first) = seen.get(&name) {
+                diagnostics.push(
+                    Diagnostic::hint(
+                        self.code(),
+                        format!("ingredient `{}` is listed more than once", ing.text),
+                        ing.position.clone(),
+                    )
+                    .with_label(first.position.clone(), "first listed here"),
+                );
+            } else {
+                seen.insert(name, ing);
+            }
+        }
+    }
+}
+
+pub struct UnproducedDerived;
+
+impl Rule for UnproducedDerived {
+    fn name(&self) -> &'static str {
+        "unproduced-derived"
+    }
+    fn code(&self) -> Code {
+        codes::UNPRODUCED_DERIVED
+    }
+    fn description(&self) -> &'static str {
+        "a derived `^` ingredient is used before any step could have produced it"
+    }
+    fn check(&self, recipe: &Recipe, diagnostics: &mut Vec<Diagnostic>) {
+        let nodes = Nodes::of(recipe);
+        for ing in nodes.ingredients {
+            if !ing.derived {
+                continue;
+            }
+            let produced = nodes
+                .operators
+                .iter()
+                .any(|op| op.position().start < ing.position.start);
+            if !produced {
+                diagnostics.push(
+                    Diagnostic::warning(
+                        self.code(),
+                        format!("derived ingredient `{}` is never produced", ing.text),
+                        ing.position.clone(),
+                    )
+                    .with_note("`^` marks an ingredient as the by product of a previous step"),
+                );
+            }
+        }
+    }
+}
+
+pub struct EmptyOperatorText;
+
+impl Rule for EmptyOperatorText {
+    fn name(&self) -> &'static str {
+        "empty-operator-text"
+    }
+    fn code(&self) -> Code {
+        codes::EMPTY_OPERATOR_TEXT
+    }
+    fn description(&self) -> &'static str {
+        "operator has no text describing the step"
+    }
+    fn check(&self, recipe: &Recipe, diagnostics: &mut Vec<Diagnostic>) {
+        for op in Nodes::of(recipe).operators {
+            if let Operand::Operator { position, text, .. } = op {
+                if text.trim().is_empty() {
+                    diagnostics.push(Diagnostic::warning(
+                        self.code(),
+                        "operator text is empty",
+                        position.clone(),
+                    ));
+                }
+            }
+        }
+    }
+}
+
+pub struct MissingTitle;
+
+impl Rule for MissingTitle {
+    fn name(&self) -> &'static str {
+        "missing-title"
+    }
+    fn code(&self) -> Code {
+        codes::MISSING_TITLE
+    }
+    fn description(&self) -> &'static str {
+        "recipe has no `**` title"
+    }
+    fn default_severity(&self) -> Option<Severity> {
+        Some(Severity::Hint)
+    }
+    fn check(&self, recipe: &Recipe, diagnostics: &mut Vec<Diagnostic>) {
+        if recipe.title.is_none() {
+            let start = recipe.position.start;
+            diagnostics.push(
+                Diagnostic::hint(self.code(), "recipe has no title", start..start + 1)
+                    .with_help("add a title after `<` with `**`"),
+            );
+        }
+    }
+}
+
+pub struct ZeroQuantity;
+
+impl Rule for ZeroQuantity {
+    fn name(&self) -> &'static str {
+        "zero-quantity"
+    }
+    fn code(&self) -> Code {
+        codes::ZERO_QUANTITY
+    }
+    fn description(&self) -> &'static str {
+        "ingredient quantity is zero"
+    }
+    fn check(&self, recipe: &Recipe, diagnostics: &mut Vec<Diagnostic>) {
+        for ing in Nodes::of(recipe).ingredients {
+            if let Some((_, 0.0)) = ing.quantities {
+                diagnostics.push(Diagnostic::warning(
+                    self.code(),
+                    format!("ingredient `{}` has a quantity of zero", ing.text),
+                    ing.position.clone(),
+                ));
+            }
+        }
+    }
+}
+
+pub struct NonCanonicalUnit;
+
+impl Rule for NonCanonicalUnit {
+    fn name(&self) -> &'static str {
+        "non-canonical-unit"
+    }
+    fn code(&self) -> Code {
+        codes::NON_CANONICAL_UNIT
+    }
+    fn description(&self) -> &'static str {
+        "unit has a preferred spelling, e.g. `lbs` instead of `lb`"
+    }
+    fn default_severity(&self) -> Option<Severity> {
+        Some(Severity::Hint)
+    }
+    fn check(&self, recipe: &Recipe, diagnostics: &mut Vec<Diagnostic>) {
+        for ing in Nodes::of(recipe).ingredients {
+            let Some(unit) = &ing.unit else { continue };
+            if let Some(canonical) = canonical_unit(unit) {
+                diagnostics.push(
+                    Diagnostic::hint(
+                        self.code(),
+                        format!("unit `{unit}` is usually written `{canonical}`"),
+                        ing.position.clone(),
+                    )
+                    .with_help(format!("replace `{unit}` with `{canonical}`")),
+                );
+            }
+        }
+    }
+}
+
+pub struct MixedDimensions;
+
+impl Rule for MixedDimensions {
+    fn name(&self) -> &'static str {
+        "mixed-dimensions"
+    }
+    fn code(&self) -> Code {
+        codes::MIXED_DIMENSIONS
+    }
+    fn description(&self) -> &'static str {
+        "the same ingredient is measured by volume, by mass or by count in different places"
+    }
+    fn check(&self, recipe: &Recipe, diagnostics: &mut Vec<Diagnostic>) {
+        let mut seen: HashMap<String, (&str, &Ingredient)> = HashMap::new();
+        for ing in Nodes::of(recipe).ingredients {
+            if ing.derived {
+                continue;
+            }
+            let Some(dimension) = dimension(ing) else {
+                continue;
+            };
+            let name = ing.text.to_lowercase();
+            match seen.get(&name) {
+                Some((first_dimension, first)) if *first_dimension != dimension => {
+                    diagnostics.push(
+                        Diagnostic::warning(
+                            self.code(),
+                            format!(
+                                "`{}` is measured by both {first_dimension} and {dimension}",
+                                ing.text
+                            ),
+                            ing.position.clone(),
+                        )
+                        .with_primary_message(format!("measured by {dimension}"))
+                        .with_label(
+                            first.position.clone(),
+                            format!("measured by {first_dimension}"),
+                        )
+                        .with_note(
+                            "amounts can only be totaled when they are measured the same way",
+                        ),
+                    );
+                }
+                Some(_) => {}
+                None => {
+                    seen.insert(name, (dimension, ing));
+                }
+            }
+        }
+    }
+}
+
+// How an ingredient is measured, units that are neither a volume nor a mass are not comparable.
+fn dimension(ing: &Ingredient) -> Option<&'static str> {
+    let amounts = ing.amounts();
+    if amounts.volume_liters().is_some() {
+        Some("volume")
+    } else if amounts.mass_kilograms().is_some() {
+        Some("mass")
+    } else if ing.unit.is_none() {
+        Some("count")
+    } else {
+        None
+    }
+}
+
+pub struct ImplausibleQuantity;
+
+impl Rule for ImplausibleQuantity {
+    fn name(&self) -> &'static str {
+        "implausible-quantity"
+    }
+    fn code(&self) -> Code {
+        codes::IMPLAUSIBLE_QUANTITY
+    }
+    fn description(&self) -> &'static str {
+        "quantity is unusually large for its unit, e.g. 40 tsp"
+    }
+    fn check(&self, recipe: &Recipe, diagnostics: &mut Vec<Diagnostic>) {
+        for ing in Nodes::of(recipe).ingredients {
+            let (Some((text, quantity)), Some(unit)) = (&ing.quantities, &ing.unit) else {
+                continue;
+            };
+            if let Some(larger) = implausible_quantity(*quantity, unit) {
+                diagnostics.push(
+                    Diagnostic::warning(
+                        self.code(),
+                        format!(
+                            "{text} {unit} of `{}` is an unusually large amount",
+                            ing.text
+                        ),
+                        ing.position.clone(),
+                    )
+                    .with_help(format!("check the quantity, or measure in `{larger}`")),
+                );
+            }
+        }
+    }
+}
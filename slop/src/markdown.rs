@@ -0,0 +1,48 @@
+use crate::{prose, semantic::Recipe};
+
+// Rendering of recipes as standalone Markdown documents.
+//
+// The ingredients are a table of quantity, unit and name and the instructions are the numbered
+// sentences of `prose`. Markdown tables can't span rows, so the tree card is only part of the
+// HTML rendering in `html`. The comment is a block quote so it may span lines.
+
+/// Render the recipe as a Markdown document.
+pub fn to_markdown(recipe: &Recipe) -> String {
+    let instructions = prose::instructions(recipe);
+    let mut out = String::new();
+    if let Some(title) = &instructions.title {
+        out += &format!("# {title}\n\n");
+    }
+    if let Some(preamble) = &instructions.preamble {
+        out += &format!("{preamble}\n\n");
+    }
+    out += "## Ingredients\n\n| Quantity | Unit | Ingredient |\n| ---: | --- | --- |\n";
+    for ingredient in recipe.ingredients() {
+        if ingredient.is_derived() {
+            continue;
+        }
+        out += &format!(
+            "| {} | {} | {} |\n",
+            escape(ingredient.quantity_text().unwrap_or_default()),
+            escape(ingredient.unit().unwrap_or_default()),
+            escape(ingredient.text())
+        );
+    }
+    out += "\n## Instructions\n\n";
+    for (number, step) in instructions.steps.iter().enumerate() {
+        out += &format!("{}. {step}\n", number + 1);
+    }
+    if let Some(comment) = &instructions.comment {
+        out += "\n";
+        for line in comment.lines() {
+            out += format!("> {line}").trim_end();
+            out += "\n";
+        }
+    }
+    out
+}
+
+// Table cells end at a `|`.
+fn escape(text: &str) -> String {
+    text.replace('|', "\\|")
+}
@@ -1,10 +1,22 @@
-use std::collections::HashMap;
+use std::{
+    collections::HashMap,
+    fmt::Display,
+    ops::{Add, Sub},
+    str::FromStr,
+    time::Duration,
+};
+
+use thiserror::Error;
 
 use crate::{
-    quant::{compute_amounts, Amounts},
-    semantic::{Operand, Recipe},
+    prep::{prep_list, PrepItem},
+    quant::Amounts,
+    semantic::{Ingredient, Operand, Recipe},
+    timeline,
+    visit::semantic::{walk_operand, Visit},
 };
 
+/// Sum the amounts of each ingredient used across the recipes, derived ingredients are skipped.
 pub fn aggregate_ingredients(recipes: impl Iterator<Item = Recipe>) -> Vec<(String, Amounts)> {
     let mut ingredients: HashMap<String, Amounts> = HashMap::new();
     for r in recipes {
@@ -18,24 +30,331 @@ pub fn aggregate_ingredients(recipes: impl Iterator<Item = Recipe>) -> Vec<(Stri
         }
     }
 
-    ingredients
-        .drain()
-        .map(|(name, amounts)| (name, amounts))
-        .collect()
+    ingredients.drain().collect()
+}
+
+// Consolidation of the prep of a menu for batch cooking.
+//
+// Prep found by `prep::prep_list` is merged across recipes when the same thing is done to the same
+// ingredient, e.g. chopping onions for three recipes becomes one task with the summed quantity.
+// Actions are compared ignoring case and spacing, ingredients also ignoring a plural ending, and a
+// batch is named after the first of the ingredients merged into it.
+
+/// Prep of one ingredient done the same way for one or more recipes.
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+pub struct BatchPrep<'a> {
+    /// The texts of the prep steps, e.g. `peel, dice`.
+    pub action: String,
+    /// The name of the ingredient as it is first written, e.g. `Onions` for `Onions` and `onion`.
+    pub ingredient: String,
+    pub amounts: Amounts,
+    /// Each prep item that was merged with the recipe it belongs to.
+    pub uses: Vec<(&'a Recipe, PrepItem<'a>)>,
+}
+
+/// Merge the prep of the recipes, in the order it first appears.
+pub fn consolidate_prep(recipes: &[Recipe]) -> Vec<BatchPrep<'_>> {
+    let mut batches: Vec<BatchPrep> = Vec::new();
+    for recipe in recipes {
+        for group in prep_list(recipe) {
+            let action = normalize(&group.action);
+            for item in group.items {
+                let ingredient = normalize_ingredient(item.ingredient.text());
+                match batches.iter_mut().find(|b| {
+                    normalize(&b.action) == action
+                        && normalize_ingredient(&b.ingredient) == ingredient
+                }) {
+                    Some(batch) => {
+                        batch.amounts.update(item.ingredient.amounts());
+                        batch.uses.push((recipe, item));
+                    }
+                    None => batches.push(BatchPrep {
+                        action: group.action.clone(),
+                        ingredient: item.ingredient.text().trim().to_string(),
+                        amounts: item.ingredient.amounts(),
+                        uses: vec![(recipe, item)],
+                    }),
+                }
+            }
+        }
+    }
+    batches
+}
+
+fn normalize(text: &str) -> String {
+    text.split_whitespace()
+        .map(str::to_lowercase)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+// Words ending in `s` that aren't plurals, besides those ending in `us` or `ss`.
+const SINGULAR: &[&str] = &["molasses", "grits", "series"];
+
+// Lowercase the name and make its last word singular, e.g. `Red Onions` becomes `red onion` while
+// `hummus` stays as it is. The result is only a key to compare names by: a word that merely looks
+// plural may still lose its ending, so show the names as they are written instead.
+pub(crate) fn normalize_ingredient(name: &str) -> String {
+    let name = normalize(name);
+    let last = name.rsplit(' ').next().unwrap_or_default();
+    if last.ends_with("us") || last.ends_with("ss") || SINGULAR.contains(&last) {
+        name
+    } else if let Some(stem) = name.strip_suffix("ies") {
+        format!("{stem}y")
+    } else if ["oes", "ches", "shes", "xes"]
+        .iter()
+        .any(|end| name.ends_with(end))
+    {
+        name[..name.len() - 2].to_string()
+    } else if name.ends_with('s') && name.len() > 3 {
+        name[..name.len() - 1].to_string()
+    } else {
+        name
+    }
+}
+
+impl Display for BatchPrep<'_> {
+    /// Writes the prep with its uses, e.g. `chop: 3 onion for Soup (saute), Chili (brown)`.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let uses: Vec<String> = self
+            .uses
+            .iter()
+            .map(|(recipe, item)| {
+                format!(
+                    "{} ({})",
+                    recipe.title().unwrap_or("Untitled"),
+                    item.used_by.text().unwrap_or_default()
+                )
+            })
+            .collect();
+        write!(
+            f,
+            "{}: {} {} for {}",
+            self.action,
+            self.amounts.to_string().trim(),
+            self.ingredient,
+            uses.join(", ")
+        )
+    }
 }
 
 fn find_ingredients(op: &Operand) -> Vec<(String, Amounts)> {
-    match op {
-        Operand::Ingredient(ing) => {
-            if ing.derived {
-                // skip derived ingredients
-                return vec![];
+    let mut finder = IngredientFinder::default();
+    finder.visit_operand(op);
+    finder.ingredients
+}
+
+#[derive(Default)]
+struct IngredientFinder {
+    ingredients: Vec<(String, Amounts)>,
+}
+
+impl<'a> Visit<'a> for IngredientFinder {
+    fn visit_operand(&mut self, op: &'a Operand) {
+        // skip operands that are not part of the recipe
+        if let Operand::UnusedOperands { .. } = op {
+            return;
+        }
+        walk_operand(self, op)
+    }
+    fn visit_ingredient(&mut self, ing: &'a Ingredient) {
+        if ing.derived {
+            // skip derived ingredients
+            return;
+        }
+        self.ingredients.push((ing.text.to_owned(), ing.amounts()));
+    }
+}
+
+// Scheduling of a menu of recipes backwards from the time it is served.
+//
+// Each recipe is scheduled with `timeline::timeline`, the steps of all recipes are then placed
+// from the serve time backwards: a step ends when the earliest of the steps using its result
+// starts, or at the serve time for the last step of a recipe. A single cook performs the active
+// steps one at a time, passive steps such as baking run alongside anything else. When the cook is
+// busy an active step is moved earlier into the latest gap that fits it.
+
+/// A time of day relative to midnight of the day the menu is served, earlier days are negative.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Clock(i64);
+
+impl Clock {
+    pub fn new(hours: u32, minutes: u32) -> Self {
+        Self(i64::from(hours) * 3600 + i64::from(minutes) * 60)
+    }
+    /// Seconds since midnight of the serve day, negative for the days before.
+    pub fn secs(&self) -> i64 {
+        self.0
+    }
+}
+
+impl Add<Duration> for Clock {
+    type Output = Clock;
+
+    fn add(self, rhs: Duration) -> Self::Output {
+        Clock(self.0 + rhs.as_secs() as i64)
+    }
+}
+
+impl Sub<Duration> for Clock {
+    type Output = Clock;
+
+    fn sub(self, rhs: Duration) -> Self::Output {
+        Clock(self.0 - rhs.as_secs() as i64)
+    }
+}
+
+#[derive(Error, Debug, PartialEq)]
+#[error("invalid time of day `{0}`, expected a time like 18:30 or 6:30pm")]
+pub struct ParseClockError(String);
+
+impl FromStr for Clock {
+    type Err = ParseClockError;
+
+    /// Parse a 24 hour time like `18:30` or a 12 hour time like `6:30pm` or `6 pm`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || ParseClockError(s.to_string());
+        let lower = s.trim().to_lowercase();
+        let (time, meridiem) = match lower.strip_suffix("am").or(lower.strip_suffix("pm")) {
+            Some(time) => (time.trim_end(), Some(lower.ends_with("pm"))),
+            None => (lower.as_str(), None),
+        };
+        let (hours, minutes) = time.split_once(':').unwrap_or((time, "0"));
+        let mut hours: u32 = hours.parse().map_err(|_| error())?;
+        let minutes: u32 = minutes.parse().map_err(|_| error())?;
+        if minutes >= 60 {
+            return Err(error());
+        }
+        match meridiem {
+            Some(pm) if (1..=12).contains(&hours) => hours = hours % 12 + if pm { 12 } else { 0 },
+            Some(_) => return Err(error()),
+            None if hours < 24 => {}
+            None => return Err(error()),
+        }
+        Ok(Clock::new(hours, minutes))
+    }
+}
+
+impl Display for Clock {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let days = self.0.div_euclid(86400);
+        let secs = self.0.rem_euclid(86400);
+        write!(f, "{:02}:{:02}", secs / 3600, secs / 60 % 60)?;
+        if days != 0 {
+            write!(f, " ({days:+}d)")?;
+        }
+        Ok(())
+    }
+}
+
+/// Steps of a menu in the order they are started.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Plan<'a> {
+    pub serve: Clock,
+    pub steps: Vec<PlannedStep<'a>>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+#[non_exhaustive]
+pub struct PlannedStep<'a> {
+    pub recipe: &'a Recipe,
+    pub operator: &'a Operand,
+    pub start: Clock,
+    pub end: Clock,
+    /// Whether the step mostly involves waiting, see [`Operand::is_passive`].
+    pub passive: bool,
+}
+
+impl PlannedStep<'_> {
+    pub fn text(&self) -> &str {
+        self.operator.text().unwrap_or_default()
+    }
+}
+
+impl<'a> Plan<'a> {
+    /// The time the first step starts.
+    pub fn start(&self) -> Clock {
+        self.steps.first().map_or(self.serve, |step| step.start)
+    }
+}
+
+/// Schedule the steps of the recipes so they are all ready to serve at `serve`.
+pub fn schedule(recipes: &[Recipe], serve: Clock) -> Plan<'_> {
+    let mut steps: Vec<(&Recipe, timeline::Step)> = Vec::new();
+    for recipe in recipes {
+        let offset = steps.len();
+        for mut step in timeline::timeline(recipe).steps {
+            step.depends_on.iter_mut().for_each(|dep| *dep += offset);
+            steps.push((recipe, step));
+        }
+    }
+    let mut dependents = vec![0; steps.len()];
+    for (_, step) in &steps {
+        for dep in &step.depends_on {
+            dependents[*dep] += 1;
+        }
+    }
+    let mut latest_end = vec![serve; steps.len()];
+    let mut placed: Vec<Option<Clock>> = vec![None; steps.len()];
+    let mut busy: Vec<(Clock, Clock)> = Vec::new();
+    // Place the steps whose dependents are all placed, latest first.
+    while let Some(index) = (0..steps.len())
+        .filter(|i| placed[*i].is_none() && dependents[*i] == 0)
+        .max_by_key(|i| (latest_end[*i], *i))
+    {
+        let step = &steps[index].1;
+        let mut end = latest_end[index];
+        if !step.passive && !step.duration.is_zero() {
+            while let Some((start, _)) = busy
+                .iter()
+                .find(|(start, stop)| *start < end && end - step.duration < *stop)
+            {
+                end = *start;
             }
-            let amounts = compute_amounts(&ing.quantities, &ing.unit);
-            vec![(ing.text.to_owned(), amounts)]
+            busy.push((end - step.duration, end));
+        }
+        let start = end - step.duration;
+        placed[index] = Some(start);
+        for dep in &step.depends_on {
+            latest_end[*dep] = latest_end[*dep].min(start);
+            dependents[*dep] -= 1;
+        }
+    }
+    let mut planned: Vec<PlannedStep> = steps
+        .iter()
+        .zip(placed)
+        .map(|((recipe, step), start)| {
+            let start = start.expect("every step is placed once its dependents are");
+            PlannedStep {
+                recipe,
+                operator: step.operator,
+                start,
+                end: start + step.duration,
+                passive: step.passive,
+            }
+        })
+        .collect();
+    planned.sort_by_key(|step| (step.start, step.end));
+    Plan {
+        serve,
+        steps: planned,
+    }
+}
+
+impl Display for Plan<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for step in &self.steps {
+            writeln!(
+                f,
+                "{}-{} {}: {}{}",
+                step.start,
+                step.end,
+                step.recipe.title().unwrap_or("Untitled"),
+                step.text(),
+                if step.passive { " (passive)" } else { "" },
+            )?;
         }
-        Operand::Operator { operands, .. } => operands.iter().flat_map(find_ingredients).collect(),
-        Operand::MissingOperand { .. } => vec![],
-        Operand::UnusedOperands { .. } => vec![],
+        writeln!(f, "{} serve", self.serve)
     }
 }
@@ -2,7 +2,10 @@ use std::{fmt::Display, iter::Peekable, str::CharIndices};
 
 use thiserror::Error;
 
-use crate::ast::{self, Position, Positioned, Quantity};
+use crate::{
+    ast::{self, Position, Positioned, Quantity},
+    diagnostic::{codes, Diagnostic},
+};
 
 #[derive(Debug)]
 pub enum Token<'input> {
@@ -158,6 +161,20 @@ impl<'input> Iterator for Lexer<'input> {
     }
 }
 
+/// Split the quantity from the start of a line using the lexer rules of ingredient quantities,
+/// e.g. `1 1/2` from `1 1/2 cups flour`. Returns the quantity and the rest of the line.
+pub(crate) fn lex_quantity(line: &str) -> Option<(&str, &str)> {
+    let mut end = None;
+    for (token, position) in Lexer::new(line) {
+        match token {
+            Token::Number(text) | Token::Fraction(text) => end = Some(position.start + text.len()),
+            _ => break,
+        }
+    }
+    let end = end?;
+    Some((line[..end].trim(), &line[end..]))
+}
+
 fn is_sentence_char(ch: char) -> bool {
     ch != '*' && ch != '#' && ch != '=' && ch != '>' && ch != ':'
 }
@@ -165,6 +182,7 @@ fn is_sentence_char(ch: char) -> bool {
 pub fn parse(input: &str) -> (ast::SourceFile, Vec<Error>) {
     let mut parser = Parser {
         lexer: Lexer::new(input).peekable(),
+        len: input.len(),
         stack: Default::default(),
         errors: Default::default(),
     };
@@ -180,6 +198,7 @@ pub fn parse(input: &str) -> (ast::SourceFile, Vec<Error>) {
 // [`Operand::UnusedOperands`] is created to consume them.
 struct Parser<'input> {
     lexer: Peekable<Lexer<'input>>,
+    len: usize,
     stack: Vec<ast::Operand>,
     errors: Vec<Error>,
 }
@@ -191,7 +210,10 @@ impl<'input> Parser<'input> {
                 position,
             )
         } else {
-            (Error::UnexpectedEOF, Position::default())
+            (
+                Error::UnexpectedEOF(self.len..self.len),
+                Position::default(),
+            )
         };
         self.errors.push(error);
         position
@@ -376,9 +398,28 @@ impl<'input> Parser<'input> {
 }
 
 #[derive(Error, Debug)]
+#[non_exhaustive]
 pub enum Error {
     #[error("unexpected token: {0}")]
     UnexpectedToken(String, Position),
     #[error("unexpected end of input")]
-    UnexpectedEOF,
+    UnexpectedEOF(Position),
+}
+
+impl From<&Error> for Diagnostic {
+    fn from(error: &Error) -> Self {
+        match error {
+            Error::UnexpectedToken(token, position) => Diagnostic::error(
+                codes::UNEXPECTED_TOKEN,
+                error.to_string(),
+                // Single character tokens have empty positions, cover the whole token.
+                position.start..position.start + token.len(),
+            )
+            .with_primary_message("unexpected token"),
+            Error::UnexpectedEOF(position) => {
+                Diagnostic::error(codes::UNEXPECTED_EOF, error.to_string(), position.clone())
+                    .with_note("recipes are enclosed in `<` and `>`")
+            }
+        }
+    }
 }
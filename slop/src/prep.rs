@@ -0,0 +1,106 @@
+use std::fmt::Display;
+
+use crate::semantic::{Ingredient, Operand, Recipe};
+
+// Extraction of the mise en place of a recipe.
+//
+// Unary operators applied directly to a raw ingredient, such as `*butter =soften` or
+// `*1: egg =separate keep white`, don't depend on any other step and can be done ahead. Chains of
+// them like `*1: onion =peel =dice` are kept together. Prep is grouped by what is done, e.g. every
+// ingredient that is diced, and each item records the step that uses the prepared ingredient. A
+// chain that is the whole recipe has no downstream use and isn't prep.
+
+/// Ingredients that are prepared the same way.
+#[derive(Clone, Debug, PartialEq)]
+#[non_exhaustive]
+pub struct PrepGroup<'a> {
+    /// The texts of the prep steps, e.g. `peel, dice`.
+    pub action: String,
+    pub items: Vec<PrepItem<'a>>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+#[non_exhaustive]
+pub struct PrepItem<'a> {
+    pub ingredient: &'a Ingredient,
+    /// Prep steps in the order they are done.
+    pub steps: Vec<&'a Operand>,
+    /// The step that uses the prepared ingredient.
+    pub used_by: &'a Operand,
+}
+
+/// The prep that can be done ahead, grouped by action in the order it first appears.
+pub fn prep_list(recipe: &Recipe) -> Vec<PrepGroup<'_>> {
+    let mut items = Vec::new();
+    collect(recipe.root(), &mut items);
+    let mut groups: Vec<PrepGroup> = Vec::new();
+    for item in items {
+        let action = item
+            .steps
+            .iter()
+            .filter_map(|step| step.text())
+            .collect::<Vec<_>>()
+            .join(", ");
+        match groups.iter_mut().find(|group| group.action == action) {
+            Some(group) => group.items.push(item),
+            None => groups.push(PrepGroup {
+                action,
+                items: vec![item],
+            }),
+        }
+    }
+    groups
+}
+
+fn collect<'a>(op: &'a Operand, items: &mut Vec<PrepItem<'a>>) {
+    let Operand::Operator { operands, .. } = op else {
+        return;
+    };
+    // The operands of a chain are reported by the step using the whole chain.
+    if chain(op).is_some() {
+        return;
+    }
+    for operand in operands {
+        match chain(operand) {
+            Some((ingredient, steps)) if !steps.is_empty() => items.push(PrepItem {
+                ingredient,
+                steps,
+                used_by: op,
+            }),
+            _ => collect(operand, items),
+        }
+    }
+}
+
+// The raw ingredient and the unary steps applied to it when the operand is such a chain.
+fn chain(op: &Operand) -> Option<(&Ingredient, Vec<&Operand>)> {
+    match op {
+        Operand::Ingredient(ingredient) if !ingredient.is_derived() => Some((ingredient, vec![])),
+        Operand::Operator { text, operands, .. }
+            if operands.len() == 1 && !matches!(text.trim(), "" | "+") =>
+        {
+            let (ingredient, mut steps) = chain(&operands[0])?;
+            steps.push(op);
+            Some((ingredient, steps))
+        }
+        _ => None,
+    }
+}
+
+impl Display for PrepGroup<'_> {
+    /// Writes the group on one line, e.g. `dice: 1 onion (for saute), 2 carrots (for simmer)`.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let items: Vec<String> = self
+            .items
+            .iter()
+            .map(|item| {
+                format!(
+                    "{} (for {})",
+                    item.ingredient,
+                    item.used_by.text().unwrap_or_default()
+                )
+            })
+            .collect();
+        write!(f, "{}: {}", self.action, items.join(", "))
+    }
+}
@@ -0,0 +1,249 @@
+use crate::{
+    html, markdown,
+    semantic::{Ingredient, Operand, Recipe},
+};
+
+// Rendering of recipes as traditional prose: an ingredient list followed by numbered
+// instructions.
+//
+// Steps are numbered in the order they are performed. Each step mentions the ingredients it uses
+// directly, results of earlier steps are referred to by number unless they come from the step
+// right before. `+` groupings become "Combine the sugar and brown sugar", or "Add the vanilla"
+// when they continue the previous step. Steps whose text already names their ingredients, as in
+// imported recipes, are used as written. The Markdown and HTML styles are the renderings of
+// `markdown` and `html`, so they match the exported documents.
+
+/// Output format of [`render`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Style {
+    Text,
+    /// The document of [`markdown::to_markdown`].
+    Markdown,
+    /// The `<article>` of [`html::article`] with the id `recipe`.
+    Html,
+}
+
+/// A recipe as prose.
+#[derive(Clone, Debug, Default, PartialEq)]
+#[non_exhaustive]
+pub struct Instructions {
+    pub title: Option<String>,
+    pub preamble: Option<String>,
+    /// Ingredients with their quantities, e.g. `2 cups flour`, derived ingredients are left out.
+    pub ingredients: Vec<String>,
+    /// One sentence per step.
+    pub steps: Vec<String>,
+    pub comment: Option<String>,
+}
+
+// Words after the verb that belong to it, e.g. `stir in`.
+const PARTICLES: &[&str] = &["in", "up", "together", "out", "off", "down", "through"];
+// Words that continue a sentence after its objects without a comma, e.g. `mix the milk to combine`.
+const CONTINUATIONS: &[&str] = &[
+    "to", "at", "with", "into", "until", "till", "for", "on", "in", "over", "under", "from", "by",
+    "and", "as",
+];
+
+/// Turn the recipe into an ingredient list and numbered sentences.
+pub fn instructions(recipe: &Recipe) -> Instructions {
+    let ingredients = recipe
+        .ingredients()
+        .into_iter()
+        .filter(|ingredient| !ingredient.is_derived())
+        .map(Ingredient::to_string)
+        .collect();
+    Instructions {
+        title: recipe.title().map(str::to_string),
+        preamble: recipe.preamble().map(str::to_string),
+        ingredients,
+        steps: steps(recipe, |ingredient| ingredient.text().to_string()),
+        comment: recipe.rendered_comment(),
+    }
+}
+
+/// The sentences of the recipe's steps, ingredients are written with `name`, e.g. to mark them
+/// up.
+pub(crate) fn steps(recipe: &Recipe, name: impl Fn(&Ingredient) -> String) -> Vec<String> {
+    let mut steps = Vec::new();
+    sentences(recipe.root(), &mut steps, &name);
+    steps
+}
+
+// Add the sentences of the operand's steps, returning the number of the step producing it.
+fn sentences(
+    op: &Operand,
+    steps: &mut Vec<String>,
+    name: &dyn Fn(&Ingredient) -> String,
+) -> Option<usize> {
+    let Operand::Operator { text, operands, .. } = op else {
+        return None;
+    };
+    let mut ingredients = Vec::new();
+    let mut numbers = Vec::new();
+    for operand in operands {
+        match operand {
+            Operand::Ingredient(ingredient) => ingredients.push(ingredient),
+            Operand::Operator { .. } => numbers.extend(sentences(operand, steps, name)),
+            Operand::MissingOperand { .. } | Operand::UnusedOperands { .. } => {}
+        }
+    }
+    // The result of the step right before is implied.
+    let continues = numbers.contains(&steps.len());
+    let text = text.trim();
+    if numbers.iter().all(|number| *number == steps.len()) {
+        if let Some(sentence) = as_written(text, &ingredients, name) {
+            steps.push(sentence + ".");
+            return Some(steps.len());
+        }
+    }
+    let mut objects: Vec<String> = ingredients
+        .iter()
+        .map(|ingredient| name(ingredient))
+        .enumerate()
+        .map(|(i, name)| {
+            if i == 0 {
+                format!("the {name}")
+            } else {
+                name.to_string()
+            }
+        })
+        .collect();
+    objects.extend(
+        numbers
+            .iter()
+            .filter(|number| **number != steps.len())
+            .map(|number| format!("the result of step {number}")),
+    );
+    let objects = join(&objects);
+    let sentence = if text == "+" || text.is_empty() {
+        match (continues, objects.is_empty()) {
+            (true, false) => format!("Add {objects}"),
+            (false, false) => format!("Combine {objects}"),
+            (_, true) => "Combine".to_string(),
+        }
+    } else if objects.is_empty() {
+        capitalize(text)
+    } else {
+        let words: Vec<&str> = text.split_whitespace().collect();
+        // A particle only belongs to the verb when it isn't followed by its own object, i.e.
+        // `stir in` but not `heat in pan`.
+        let continues_at = |i: usize| {
+            words.get(i).is_none_or(|word| {
+                let word = word.to_lowercase();
+                CONTINUATIONS.contains(&word.as_str())
+                    || word.starts_with(|c: char| c.is_ascii_digit())
+            })
+        };
+        let verb_len = match words.get(1) {
+            Some(word) if PARTICLES.contains(&word.to_lowercase().as_str()) && continues_at(2) => 2,
+            _ => 1,
+        };
+        let (verb, rest) = words.split_at(verb_len);
+        let mut sentence = format!("{} {objects}", capitalize(&verb.join(" ")));
+        if !rest.is_empty() {
+            if !continues_at(verb_len) {
+                sentence.push(',');
+            }
+            sentence.push(' ');
+            sentence.push_str(&rest.join(" "));
+        }
+        sentence
+    };
+    steps.push(sentence + ".");
+    Some(steps.len())
+}
+
+// The text as a sentence when it already mentions each of the ingredients, e.g. `crack the eggs
+// into a bowl` in recipes imported from other formats.
+fn as_written(
+    text: &str,
+    ingredients: &[&Ingredient],
+    name: &dyn Fn(&Ingredient) -> String,
+) -> Option<String> {
+    if ingredients.is_empty() {
+        return None;
+    }
+    let lowercase = text.to_ascii_lowercase();
+    let mut mentions: Vec<(std::ops::Range<usize>, &Ingredient)> = Vec::new();
+    for ingredient in ingredients {
+        let word = ingredient.text().to_ascii_lowercase();
+        let span = lowercase
+            .match_indices(&word)
+            .map(|(start, word)| start..start + word.len())
+            .find(|span| {
+                let before = lowercase[..span.start].chars().next_back();
+                let after = lowercase[span.end..].chars().next();
+                !before.is_some_and(char::is_alphanumeric)
+                    && !after.is_some_and(char::is_alphanumeric)
+                    && !mentions
+                        .iter()
+                        .any(|(other, _)| other.start < span.end && span.start < other.end)
+            })?;
+        mentions.push((span, ingredient));
+    }
+    mentions.sort_by_key(|(span, _)| span.start);
+    let mut sentence = String::new();
+    let mut end = 0;
+    for (span, ingredient) in mentions {
+        sentence += &text[end..span.start];
+        sentence += &name(ingredient);
+        end = span.end;
+    }
+    sentence += &text[end..];
+    Some(capitalize(&sentence))
+}
+
+fn capitalize(text: &str) -> String {
+    let mut chars = text.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+// Join items like `a, b and c`.
+fn join(items: &[String]) -> String {
+    match items.split_last() {
+        Some((last, [])) => last.clone(),
+        Some((last, rest)) => format!("{} and {last}", rest.join(", ")),
+        None => String::new(),
+    }
+}
+
+/// Render the recipe as prose in the given style.
+pub fn render(recipe: &Recipe, style: Style) -> String {
+    match style {
+        Style::Text => to_text(&instructions(recipe)),
+        Style::Markdown => markdown::to_markdown(recipe),
+        Style::Html => html::article(recipe, "recipe"),
+    }
+}
+
+fn to_text(instructions: &Instructions) -> String {
+    let mut out = String::new();
+    if let Some(title) = &instructions.title {
+        out += &format!("{title}\n\n");
+    }
+    if let Some(preamble) = &instructions.preamble {
+        out += &format!("{preamble}\n\n");
+    }
+    out += "Ingredients\n";
+    for ingredient in &instructions.ingredients {
+        out += &format!("- {ingredient}\n");
+    }
+    out += "\nInstructions\n";
+    for (number, step) in instructions.steps.iter().enumerate() {
+        out += &format!("{}. {step}\n", number + 1);
+    }
+    if let Some(comment) = &instructions.comment {
+        out += &format!("\n{comment}\n");
+    }
+    out
+}
+
+pub(crate) fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
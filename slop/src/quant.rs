@@ -60,6 +60,18 @@ impl Display for Amounts {
     }
 }
 impl Amounts {
+    /// Total volume in liters, if any of the quantities were a volume.
+    pub fn volume_liters(&self) -> Option<f64> {
+        self.volume.map(|v| v.as_liters())
+    }
+    /// Total mass in kilograms, if any of the quantities were a mass.
+    pub fn mass_kilograms(&self) -> Option<f64> {
+        self.mass.map(|m| m.as_kilograms())
+    }
+    /// Quantities with units that cannot be converted, keyed by their unit.
+    pub fn arbitrary(&self) -> impl Iterator<Item = &Arbitrary> {
+        self.arbitrary.values()
+    }
     pub fn update(&mut self, mut other: Amounts) {
         match (self.volume, other.volume) {
             (Some(s), Some(o)) => self.volume = Some(s + o),
@@ -112,12 +124,20 @@ impl From<(String, Arbitrary)> for Amounts {
 
 pub fn compute_amounts(quantity: &Option<(String, f64)>, unit: &Option<String>) -> Amounts {
     match (quantity, unit) {
-        (Some((_, q)), Some(u)) => match u.as_str() {
+        (Some((_, q)), Some(u)) => match u.to_lowercase().as_str() {
             "cup" | "cups" => Volume::from_cups(*q).into(),
             "tablespoon" | "tablespoons" | "tbsp" => Volume::from_tablespoons(*q).into(),
+            "teaspoon" | "teaspoons" | "tsp" => Volume::from_teaspoons(*q).into(),
             "fl oz" => Volume::from_fluid_ounces(*q).into(),
-            "oz" => Mass::from_ounces(*q).into(),
-            "lbs" | "lb" => Mass::from_pounds(*q).into(),
+            "pint" | "pints" | "pt" => Volume::from_milliliters(*q * 473.176).into(),
+            "quart" | "quarts" | "qt" => Volume::from_milliliters(*q * 946.353).into(),
+            "gal" | "gallon" | "gallons" => Volume::from_gallons(*q).into(),
+            "ml" | "milliliter" | "milliliters" => Volume::from_milliliters(*q).into(),
+            "l" | "liter" | "liters" | "litre" | "litres" => Volume::from_liters(*q).into(),
+            "oz" | "ounce" | "ounces" => Mass::from_ounces(*q).into(),
+            "lbs" | "lb" | "pound" | "pounds" => Mass::from_pounds(*q).into(),
+            "g" | "gram" | "grams" => Mass::from_grams(*q).into(),
+            "kg" | "kilogram" | "kilograms" => Mass::from_kilograms(*q).into(),
             _ => (
                 u.to_owned(),
                 Arbitrary {
@@ -153,3 +173,119 @@ pub fn compute_amounts(quantity: &Option<(String, f64)>, unit: &Option<String>)
             .into(),
     }
 }
+
+// Units recognized as measures. Volume and mass units can be converted by compute_amounts, the
+// rest are counts of containers or portions.
+#[rustfmt::skip]
+const KNOWN_UNITS: &[&str] = &[
+    // volume
+    "tsp", "teaspoon", "teaspoons", "tbsp", "tablespoon", "tablespoons", "cup", "cups", "fl oz",
+    "pint", "pints", "pt", "quart", "quarts", "qt", "gal", "gallon", "gallons", "ml", "milliliter",
+    "milliliters", "l", "liter", "liters", "litre", "litres",
+    // mass
+    "oz", "ounce", "ounces", "lb", "lbs", "pound", "pounds", "g", "gram", "grams", "kg",
+    "kilogram", "kilograms",
+    // counts
+    "of", "pinch", "pinches", "dash", "dashes", "drop", "drops", "clove", "cloves", "slice",
+    "slices", "piece", "pieces", "stick", "sticks", "can", "cans", "jar", "jars", "bottle",
+    "bottles", "package", "packages", "pkg", "pouch", "pouches", "bag", "bags", "box", "boxes",
+    "bunch", "bunches", "sprig", "sprigs", "head", "heads", "handful", "handfuls", "sheet",
+    "sheets", "tablet", "tablets", "whole", "large", "medium", "small",
+];
+
+/// Reports whether the unit is a recognized unit of measure.
+///
+/// Only the last word of the unit is considered so qualified units like `heaping tbsp` are known.
+pub fn is_known_unit(unit: &str) -> bool {
+    let unit = unit.trim().to_lowercase();
+    KNOWN_UNITS.contains(&unit.as_str())
+        || unit
+            .rsplit(' ')
+            .next()
+            .is_some_and(|word| KNOWN_UNITS.contains(&word))
+}
+
+/// Suggest the known unit closest in spelling to an unknown one, e.g. `tbsp` for `tbps`.
+///
+/// Like [`is_known_unit`] only the last word of the unit is considered.
+pub fn suggest_unit(unit: &str) -> Option<&'static str> {
+    let unit = unit.trim().to_lowercase();
+    let word = unit.rsplit(' ').next()?;
+    // Allow one edit for every three characters so short units only match near misses.
+    let max_distance = (word.chars().count() / 3).max(1);
+    KNOWN_UNITS
+        .iter()
+        .filter(|known| !known.contains(' '))
+        .map(|known| (edit_distance(word, known), *known))
+        .filter(|(distance, _)| *distance <= max_distance)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, known)| known)
+}
+
+// Optimal string alignment distance, the number of insertions, deletions, substitutions and
+// transpositions of adjacent characters needed to turn a into b.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let mut d = vec![vec![0; b.len() + 1]; a.len() + 1];
+    for (i, row) in d.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for (j, cell) in d[0].iter_mut().enumerate() {
+        *cell = j;
+    }
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            d[i][j] = (d[i - 1][j] + 1)
+                .min(d[i][j - 1] + 1)
+                .min(d[i - 1][j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                d[i][j] = d[i][j].min(d[i - 2][j - 2] + 1);
+            }
+        }
+    }
+    d[a.len()][b.len()]
+}
+
+// Largest plausible quantity of a unit and the larger unit to use above it.
+const PLAUSIBLE_MAXIMUMS: &[(&[&str], f64, &str)] = &[
+    (&["tsp", "teaspoon", "teaspoons"], 12.0, "tbsp"),
+    (&["tbsp", "tablespoon", "tablespoons"], 16.0, "cup"),
+    (&["oz", "ounce", "ounces"], 64.0, "lbs"),
+    (&["ml", "milliliter", "milliliters"], 2000.0, "l"),
+    (&["g", "gram", "grams"], 5000.0, "kg"),
+];
+
+/// Reports the larger unit that should be used when the quantity is implausibly large for its
+/// unit, e.g. `tbsp` for 40 tsp.
+pub fn implausible_quantity(quantity: f64, unit: &str) -> Option<&'static str> {
+    let unit = unit.trim().to_lowercase();
+    PLAUSIBLE_MAXIMUMS
+        .iter()
+        .find(|(units, _, _)| units.contains(&unit.as_str()))
+        .filter(|(_, max, _)| quantity > *max)
+        .map(|(_, _, larger)| *larger)
+}
+
+// Preferred spelling of units that are commonly written several ways.
+const CANONICAL_UNITS: &[(&str, &str)] = &[
+    ("lb", "lbs"),
+    ("pound", "lbs"),
+    ("pounds", "lbs"),
+    ("ounce", "oz"),
+    ("ounces", "oz"),
+    ("tablespoon", "tbsp"),
+    ("tablespoons", "tbsp"),
+    ("teaspoon", "tsp"),
+    ("teaspoons", "tsp"),
+];
+
+/// Returns the preferred spelling of the unit when it is written differently, ignoring case.
+pub fn canonical_unit(unit: &str) -> Option<&'static str> {
+    let unit = unit.to_lowercase();
+    CANONICAL_UNITS
+        .iter()
+        .find(|(alias, _)| *alias == unit)
+        .map(|(_, canonical)| *canonical)
+}
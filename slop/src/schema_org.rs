@@ -0,0 +1,95 @@
+use serde::Serialize;
+
+use crate::{prose, semantic, timeline::timeline};
+
+// Export of compiled recipes as schema.org `Recipe` JSON-LD, for embedding in web pages so search
+// engines can index them. See `import::schema_org` for the reverse direction.
+//
+// Instructions are the numbered sentences of `prose`. Times are only included when the steps have
+// durations: the prep time is the time spent working, the cook time the time spent waiting and the
+// total time the length of the timeline, where independent steps overlap.
+
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct Recipe {
+    #[serde(rename = "@context")]
+    pub context: String,
+    #[serde(rename = "@type")]
+    pub kind: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub recipe_yield: Option<String>,
+    pub recipe_ingredient: Vec<String>,
+    pub recipe_instructions: Vec<HowToStep>,
+    /// ISO 8601 durations, e.g. `PT1H30M`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub prep_time: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cook_time: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub total_time: Option<String>,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+#[non_exhaustive]
+pub struct HowToStep {
+    #[serde(rename = "@type")]
+    pub kind: String,
+    pub position: usize,
+    pub text: String,
+}
+
+pub fn to_recipe(r: &semantic::Recipe) -> Recipe {
+    let instructions = prose::instructions(r);
+    let times = r.times();
+    Recipe {
+        context: "https://schema.org".to_string(),
+        kind: "Recipe".to_string(),
+        name: r.title().map(str::to_string),
+        description: r.preamble().map(str::to_string),
+        recipe_yield: r.recipe_yield().map(str::to_string),
+        recipe_ingredient: instructions.ingredients,
+        recipe_instructions: instructions
+            .steps
+            .into_iter()
+            .enumerate()
+            .map(|(i, text)| HowToStep {
+                kind: "HowToStep".to_string(),
+                position: i + 1,
+                text,
+            })
+            .collect(),
+        prep_time: iso_duration(times.active.max),
+        cook_time: iso_duration(times.passive.max),
+        total_time: iso_duration(timeline(r).total),
+    }
+}
+
+/// Serialize the recipes of the file as JSON-LD, a single recipe is written as an object and
+/// several as a list.
+pub fn to_string(file: &semantic::SourceFile) -> String {
+    let recipes: Vec<Recipe> = file.recipes().iter().map(to_recipe).collect();
+    match recipes.as_slice() {
+        [recipe] => serde_json::to_string_pretty(recipe),
+        recipes => serde_json::to_string_pretty(recipes),
+    }
+    .expect("recipe should serialize to JSON")
+}
+
+fn iso_duration(duration: std::time::Duration) -> Option<String> {
+    let secs = duration.as_secs();
+    if secs == 0 {
+        return None;
+    }
+    let mut iso = "PT".to_string();
+    for (value, unit) in [(secs / 3600, 'H'), (secs / 60 % 60, 'M'), (secs % 60, 'S')] {
+        if value > 0 {
+            iso += &format!("{value}{unit}");
+        }
+    }
+    Some(iso)
+}
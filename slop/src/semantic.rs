@@ -1,13 +1,23 @@
-use crate::ast::{self, Position};
+use crate::{
+    annotation::{annotate, is_passive, Annotation, AnnotationKind, Duration, Times},
+    ast::{self, Position},
+    diagnostic::{codes, Diagnostic},
+    quant::{compute_amounts, Amounts},
+    visit::semantic::{walk_operand, Visit},
+};
 
 use thiserror::Error;
 
-#[derive(Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
 pub struct SourceFile {
     pub recipes: Vec<Recipe>,
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
 pub struct Recipe {
     pub position: Position,
     pub title: Option<String>,
@@ -17,6 +27,8 @@ pub struct Recipe {
 }
 
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
 pub struct Ingredient {
     pub position: Position,
     pub derived: bool,
@@ -26,12 +38,18 @@ pub struct Ingredient {
 }
 
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
 pub enum Operand {
     Ingredient(Ingredient),
+    #[non_exhaustive]
     Operator {
         position: Position,
         text: String,
         operands: Vec<Operand>,
+        /// Durations, temperatures and heat levels found in the text.
+        #[cfg_attr(feature = "serde", serde(default))]
+        annotations: Vec<Annotation>,
     },
     MissingOperand {
         position: Position,
@@ -42,6 +60,176 @@ pub enum Operand {
     },
 }
 
+impl SourceFile {
+    pub fn recipes(&self) -> &[Recipe] {
+        &self.recipes
+    }
+}
+
+impl Recipe {
+    pub fn title(&self) -> Option<&str> {
+        self.title.as_deref()
+    }
+    pub fn preamble(&self) -> Option<&str> {
+        self.preamble.as_deref()
+    }
+    pub fn comment(&self) -> Option<&str> {
+        self.comment.as_deref()
+    }
+    /// The comment without its lines starting with `TODO`, which are notes for whoever edits the
+    /// source, e.g. the guesses of an import, rather than for the cook.
+    pub fn rendered_comment(&self) -> Option<String> {
+        let lines: Vec<&str> = self
+            .comment
+            .as_deref()?
+            .lines()
+            .filter(|line| !line.trim_start().starts_with("TODO"))
+            .collect();
+        let comment = lines.join("\n");
+        (!comment.trim().is_empty()).then_some(comment)
+    }
+    /// The yield of the recipe taken from the first line of a comment such as `Makes 2 pancakes`
+    /// or `Serves 4`.
+    ///
+    /// Returns the text after the leading keyword, i.e. `2 pancakes` or `4`.
+    pub fn recipe_yield(&self) -> Option<&str> {
+        let comment = self.comment.as_deref()?.trim().lines().next()?;
+        let (keyword, rest) = comment.split_once(char::is_whitespace)?;
+        match keyword.to_lowercase().as_str() {
+            "makes" | "serves" | "yield" | "yields" => Some(rest.trim()),
+            _ => None,
+        }
+    }
+    pub fn root(&self) -> &Operand {
+        &self.root
+    }
+    /// All ingredients of the recipe in source order, including derived ingredients.
+    pub fn ingredients(&self) -> Vec<&Ingredient> {
+        let mut collector = Collector::default();
+        collector.visit_operand(&self.root);
+        collector.ingredients
+    }
+    /// All operators of the recipe in the order they are performed.
+    pub fn steps(&self) -> Vec<&Operand> {
+        let mut collector = Collector::default();
+        collector.visit_operand(&self.root);
+        collector.steps
+    }
+    /// Total time of the steps of the recipe, steps are passive when they mostly involve waiting.
+    pub fn times(&self) -> Times {
+        let mut times = Times::default();
+        for step in self.steps() {
+            let duration = step.duration();
+            if step.is_passive() {
+                times.passive += duration;
+            } else {
+                times.active += duration;
+            }
+        }
+        times
+    }
+}
+
+#[derive(Default)]
+struct Collector<'a> {
+    ingredients: Vec<&'a Ingredient>,
+    steps: Vec<&'a Operand>,
+}
+
+impl<'a> Visit<'a> for Collector<'a> {
+    fn visit_operand(&mut self, op: &'a Operand) {
+        walk_operand(self, op);
+        if let Operand::Operator { .. } = op {
+            self.steps.push(op);
+        }
+    }
+    fn visit_ingredient(&mut self, ingredient: &'a Ingredient) {
+        self.ingredients.push(ingredient);
+    }
+}
+
+impl Ingredient {
+    pub fn text(&self) -> &str {
+        self.text.as_str()
+    }
+    pub fn is_derived(&self) -> bool {
+        self.derived
+    }
+    /// The quantity as written in the source, e.g. `1 1/2`.
+    pub fn quantity_text(&self) -> Option<&str> {
+        self.quantities.as_ref().map(|(text, _)| text.as_str())
+    }
+    pub fn quantity(&self) -> Option<f64> {
+        self.quantities.as_ref().map(|(_, value)| *value)
+    }
+    pub fn unit(&self) -> Option<&str> {
+        self.unit.as_deref()
+    }
+    pub fn amounts(&self) -> Amounts {
+        compute_amounts(&self.quantities, &self.unit)
+    }
+}
+
+impl std::fmt::Display for Ingredient {
+    /// Writes the ingredient as it is read out, e.g. `2 cups flour`.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(quantity) = self.quantity_text() {
+            write!(f, "{quantity} ")?;
+        }
+        if let Some(unit) = self.unit() {
+            write!(f, "{unit} ")?;
+        }
+        write!(f, "{}", self.text)
+    }
+}
+
+impl Operand {
+    pub fn position(&self) -> &Position {
+        match self {
+            Operand::Ingredient(ingredient) => &ingredient.position,
+            Operand::Operator { position, .. }
+            | Operand::MissingOperand { position }
+            | Operand::UnusedOperands { position, .. } => position,
+        }
+    }
+    /// The text of an ingredient or operator.
+    pub fn text(&self) -> Option<&str> {
+        match self {
+            Operand::Ingredient(ingredient) => Some(ingredient.text.as_str()),
+            Operand::Operator { text, .. } => Some(text.as_str()),
+            Operand::MissingOperand { .. } | Operand::UnusedOperands { .. } => None,
+        }
+    }
+    pub fn annotations(&self) -> &[Annotation] {
+        match self {
+            Operand::Operator { annotations, .. } => annotations,
+            _ => &[],
+        }
+    }
+    /// Time the step takes, the sum of the durations in its text.
+    pub fn duration(&self) -> Duration {
+        self.annotations()
+            .iter()
+            .filter_map(|annotation| match &annotation.kind {
+                AnnotationKind::Duration(duration) => Some(*duration),
+                _ => None,
+            })
+            .sum()
+    }
+    /// Reports whether the step mostly involves waiting, see [`crate::annotation::is_passive`].
+    pub fn is_passive(&self) -> bool {
+        matches!(self, Operand::Operator { text, .. } if is_passive(text))
+    }
+    pub fn operands(&self) -> &[Operand] {
+        match self {
+            Operand::Operator { operands, .. } | Operand::UnusedOperands { operands, .. } => {
+                operands
+            }
+            Operand::Ingredient(_) | Operand::MissingOperand { .. } => &[],
+        }
+    }
+}
+
 pub fn convert_source_file(f: &ast::SourceFile) -> (SourceFile, Vec<Error>) {
     let mut errors = Vec::new();
     (
@@ -104,6 +292,7 @@ pub fn convert_operand(op: &ast::Operand, errors: &mut Vec<Error>) -> Operand {
             position: position.clone(),
             operands: vec![convert_operand(operand, errors)],
             text: text.clone(),
+            annotations: annotate(text),
         },
         ast::Operand::BinaryOp {
             position,
@@ -128,6 +317,7 @@ pub fn convert_operand(op: &ast::Operand, errors: &mut Vec<Error>) -> Operand {
                 position: position.clone(),
                 text: text.clone(),
                 operands: ops,
+                annotations: annotate(text),
             }
         }
         ast::Operand::MissingOperand { position } => {
@@ -150,9 +340,32 @@ pub fn convert_operand(op: &ast::Operand, errors: &mut Vec<Error>) -> Operand {
 }
 
 #[derive(Error, Debug)]
+#[non_exhaustive]
 pub enum Error {
     #[error("missing operand")]
     MissingOperand(Position),
     #[error("unused operands: {0}")]
     UnusedOperands(usize, Position),
 }
+
+impl From<&Error> for Diagnostic {
+    fn from(error: &Error) -> Self {
+        match error {
+            Error::MissingOperand(position) => Diagnostic::error(
+                codes::MISSING_OPERAND,
+                "missing operand",
+                // Missing operands are positioned at the operator character.
+                position.start..position.end.max(position.start + 1),
+            )
+            .with_primary_message("operator is missing an operand")
+            .with_note("`=` operators take one operand and `#` operators take two"),
+            Error::UnusedOperands(count, position) => Diagnostic::error(
+                codes::UNUSED_OPERANDS,
+                format!("found {count} unused operands"),
+                position.clone(),
+            )
+            .with_primary_message("operands are not used by an operator")
+            .with_help("combine the operands into a single step, e.g. with `#+`"),
+        }
+    }
+}
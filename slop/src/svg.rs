@@ -1,6 +1,7 @@
 use std::cmp::{max, min};
 
 use crate::semantic::{self, Ingredient, Operand, Recipe};
+use crate::{annotation, prep, timeline::Timeline};
 use svg::node::element::{Group, Rectangle, Style, TSpan, Text};
 use svg::node::Text as RawText;
 use svg::Document;
@@ -127,14 +128,14 @@ impl Builder {
         }
     }
     fn build_comment(&mut self, r: &Recipe) -> Option<Text> {
-        if let Some(ref comment) = r.comment {
+        if let Some(comment) = r.rendered_comment() {
             render_text(
-                comment,
+                &comment,
                 Point {
                     x: 0,
                     y: self.bottom,
                 },
-                usize::max_value(),
+                usize::MAX,
                 self.max_width,
                 0,
                 false,
@@ -151,6 +152,32 @@ impl Builder {
             None
         }
     }
+    // List the prep that can be done ahead below the tree, one line per group.
+    fn build_prep(&mut self, r: &Recipe) -> Option<(Group, usize)> {
+        let groups = prep::prep_list(r);
+        if groups.is_empty() {
+            return None;
+        }
+        let lines = std::iter::once("Mise en place".to_string())
+            .chain(groups.iter().map(|group| format!("- {group}")));
+        let mut g = Group::new();
+        let mut width = 0;
+        for (i, line) in lines.enumerate() {
+            let y = self.bottom + LINE_HEIGHT + Y_MARGIN;
+            self.bottom = y;
+            width = max(width, X_MARGIN + line.chars().count() * CHAR_WIDTH);
+            let mut text = Text::new()
+                .add(RawText::new(line))
+                .set("x", X_MARGIN)
+                .set("y", y);
+            if i == 0 {
+                text = text.set("font-style", "bold");
+            }
+            g = g.add(text);
+        }
+        self.bottom += Y_MARGIN;
+        Some((g, width))
+    }
     fn build_operand(&mut self, op: &Operand) -> (Group, BoundingBox) {
         let (mut g, b) = match op {
             Operand::Ingredient(i) => {
@@ -161,7 +188,7 @@ impl Builder {
                         x: 0,
                         y: self.bottom,
                     },
-                    usize::max_value(),
+                    usize::MAX,
                     INGREDIENT_WIDTH,
                     0,
                     false,
@@ -212,6 +239,7 @@ impl Builder {
                     position: position.clone(),
                     text: "*UNUSED*".to_string(),
                     operands: operands.clone(),
+                    annotations: Vec::new(),
                 })
             }
         };
@@ -257,6 +285,11 @@ rect {
     let (op_doc, mut bound) = builder.build_operand(&r.root);
     doc = doc.add(op_doc);
 
+    if let Some((prep, width)) = builder.build_prep(r) {
+        doc = doc.add(prep);
+        bound.bottom_right.x = max(bound.bottom_right.x, width);
+        bound.bottom_right.y = builder.bottom;
+    }
     if let Some(comment) = builder.build_comment(r) {
         doc = doc.add(comment);
         // Add room for comment line
@@ -364,3 +397,115 @@ fn render_text(
 
     (text_node, bounds)
 }
+
+const GANTT_LABEL_WIDTH: usize = 300; // 30 characters
+const GANTT_BAR_WIDTH: usize = 600;
+const GANTT_ROW_HEIGHT: usize = LINE_HEIGHT + Y_MARGIN;
+
+// Draw the timeline of a recipe as a Gantt chart.
+//
+// Every step is a row with its text on the left and a bar on the right spanning from the start
+// to the end of the step. Passive steps are drawn lighter than active steps and steps on the
+// critical path are outlined in red. Steps that take no time are drawn as thin bars.
+pub fn timeline_to_svg(r: &Recipe, timeline: &Timeline) -> String {
+    let mut doc = Document::new().add(Style::new(
+        r#"text {
+    font-family: monospace;
+    font-size: 16px;
+}
+rect {
+    stroke: black;
+    stroke-width: 1;
+}
+rect.active {
+    fill: steelblue;
+}
+rect.passive {
+    fill: lightsteelblue;
+}
+rect.critical {
+    stroke: red;
+    stroke-width: 2;
+}"#,
+    ));
+    let mut y = Y_MARGIN + LINE_HEIGHT;
+    let title = format!(
+        "{} ({})",
+        r.title.as_deref().unwrap_or("Timeline"),
+        annotation::Duration::from(timeline.total)
+    );
+    doc = doc.add(
+        Text::new()
+            .add(RawText::new(title))
+            .set("font-size", "18px")
+            .set("x", X_MARGIN)
+            .set("y", y),
+    );
+    y += Y_MARGIN;
+
+    let total = timeline.total.as_secs_f64();
+    let scale = |d: std::time::Duration| {
+        if total > 0.0 {
+            (d.as_secs_f64() / total * GANTT_BAR_WIDTH as f64).round() as usize
+        } else {
+            0
+        }
+    };
+    for step in &timeline.steps {
+        let max_chars = GANTT_LABEL_WIDTH / CHAR_WIDTH - 1;
+        let label: String = if step.text().chars().count() > max_chars {
+            step.text()
+                .chars()
+                .take(max_chars - 1)
+                .chain(['…'])
+                .collect()
+        } else {
+            step.text().to_string()
+        };
+        doc = doc.add(
+            Text::new()
+                .add(RawText::new(label))
+                .set("x", X_MARGIN)
+                .set("y", y + LINE_HEIGHT - Y_MARGIN),
+        );
+        let mut class = if step.passive { "passive" } else { "active" }.to_string();
+        if step.critical {
+            class.push_str(" critical");
+        }
+        doc = doc.add(
+            Rectangle::new()
+                .set("class", class)
+                .set("x", GANTT_LABEL_WIDTH + scale(step.start))
+                .set("y", y + Y_MARGIN / 2)
+                .set("width", max(scale(step.duration), 2))
+                .set("height", LINE_HEIGHT - Y_MARGIN),
+        );
+        y += GANTT_ROW_HEIGHT;
+    }
+
+    // Time axis with the start and end of the recipe.
+    for (x, text, anchor) in [
+        (GANTT_LABEL_WIDTH, "0".to_string(), "start"),
+        (
+            GANTT_LABEL_WIDTH + GANTT_BAR_WIDTH,
+            annotation::Duration::from(timeline.total).to_string(),
+            "end",
+        ),
+    ] {
+        doc = doc.add(
+            Text::new()
+                .add(RawText::new(text))
+                .set("text-anchor", anchor)
+                .set("x", x)
+                .set("y", y + LINE_HEIGHT),
+        );
+    }
+    y += LINE_HEIGHT + Y_MARGIN;
+
+    let doc = doc
+        .set("width", GANTT_LABEL_WIDTH + GANTT_BAR_WIDTH + X_MARGIN * 2)
+        .set("height", y + Y_MARGIN);
+    let mut out: Vec<u8> = Vec::new();
+    svg::write(&mut out, &doc).unwrap();
+    String::from_utf8(out).expect("svg should be valid utf-8")
+}
@@ -0,0 +1,118 @@
+use std::{fmt::Display, time::Duration};
+
+use crate::{
+    annotation,
+    semantic::{Operand, Recipe},
+};
+
+// Scheduling of the steps of a recipe.
+//
+// The recipe tree is a dependency graph, a step can start once the steps producing its operands
+// have finished. Steps in independent branches run in parallel, e.g. heating a pan while mixing
+// a batter. Steps take the upper bound of their duration, steps without a duration take no time.
+// A `+` only groups its operands so it isn't a step, the steps using it depend on its operands.
+
+#[derive(Clone, Debug, PartialEq)]
+#[non_exhaustive]
+pub struct Timeline<'a> {
+    /// Steps in the order they are written, every step comes after the steps it depends on.
+    pub steps: Vec<Step<'a>>,
+    /// Elapsed time from the start of the first step to the end of the last.
+    pub total: Duration,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+#[non_exhaustive]
+pub struct Step<'a> {
+    pub operator: &'a Operand,
+    /// Indexes of the steps that must finish before this step can start.
+    pub depends_on: Vec<usize>,
+    pub start: Duration,
+    pub duration: Duration,
+    /// Whether the step mostly involves waiting, see [`Operand::is_passive`].
+    pub passive: bool,
+    /// Whether the step is on the critical path, delaying it delays the whole recipe.
+    pub critical: bool,
+}
+
+impl Step<'_> {
+    pub fn end(&self) -> Duration {
+        self.start + self.duration
+    }
+    pub fn text(&self) -> &str {
+        self.operator.text().unwrap_or_default()
+    }
+}
+
+impl<'a> Timeline<'a> {
+    /// Steps of the critical path in the order they are performed.
+    pub fn critical_path(&self) -> impl Iterator<Item = &Step<'a>> {
+        self.steps.iter().filter(|step| step.critical)
+    }
+}
+
+/// Compute the earliest start of every step of the recipe and its critical path.
+pub fn timeline(recipe: &Recipe) -> Timeline<'_> {
+    let mut steps = Vec::new();
+    schedule(&recipe.root, &mut steps);
+    let total = steps.iter().map(Step::end).max().unwrap_or_default();
+
+    // Walk back from the step that finishes last through the dependencies that finish last.
+    let mut current = steps.iter().rposition(|step| step.end() == total);
+    while let Some(index) = current {
+        steps[index].critical = true;
+        current = steps[index]
+            .depends_on
+            .iter()
+            .copied()
+            .max_by_key(|dep| steps[*dep].end());
+    }
+    Timeline { steps, total }
+}
+
+// Add the steps of the operand returning the indexes of the steps producing it, several for a
+// `+`.
+fn schedule<'a>(op: &'a Operand, steps: &mut Vec<Step<'a>>) -> Vec<usize> {
+    let depends_on: Vec<usize> = op
+        .operands()
+        .iter()
+        .flat_map(|operand| schedule(operand, steps))
+        .collect();
+    match op {
+        Operand::Operator { text, .. } if matches!(text.trim(), "" | "+") => return depends_on,
+        Operand::Operator { .. } => {}
+        _ => return Vec::new(),
+    }
+    let start = depends_on
+        .iter()
+        .map(|dep| steps[*dep].end())
+        .max()
+        .unwrap_or_default();
+    steps.push(Step {
+        operator: op,
+        depends_on,
+        start,
+        duration: op.duration().max,
+        passive: op.is_passive(),
+        critical: false,
+    });
+    vec![steps.len() - 1]
+}
+
+impl Display for Timeline<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "total {}", annotation::Duration::from(self.total))?;
+        for step in &self.steps {
+            writeln!(
+                f,
+                "{:>9} {:>9} {} {}{}",
+                annotation::Duration::from(step.start).to_string(),
+                annotation::Duration::from(step.end()).to_string(),
+                if step.critical { '*' } else { ' ' },
+                step.text(),
+                if step.passive { " (passive)" } else { "" },
+            )?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,308 @@
+// Traversal traits for the syntax and semantic trees.
+//
+// Each tree has three traits:
+//
+// * `Visit` walks a tree by shared reference.
+// * `VisitMut` walks a tree by mutable reference, modifying it in place.
+// * `Fold` consumes a tree and builds a new one.
+//
+// Every method has a default implementation that walks into the children of the node using the
+// matching `walk_*`/`fold_*` function. Implementations override the methods for the nodes they
+// care about and call the walk function to continue into the children.
+
+/// Traversal of [`crate::ast`] trees.
+pub mod ast {
+    use crate::ast::{Operand, Quantity, Recipe, SourceFile};
+
+    pub trait Visit<'a> {
+        fn visit_source_file(&mut self, file: &'a SourceFile) {
+            walk_source_file(self, file)
+        }
+        fn visit_recipe(&mut self, recipe: &'a Recipe) {
+            walk_recipe(self, recipe)
+        }
+        fn visit_operand(&mut self, op: &'a Operand) {
+            walk_operand(self, op)
+        }
+        fn visit_quantity(&mut self, _quantity: &'a Quantity) {}
+    }
+
+    pub fn walk_source_file<'a, V: Visit<'a> + ?Sized>(v: &mut V, file: &'a SourceFile) {
+        for recipe in &file.recipes {
+            v.visit_recipe(recipe);
+        }
+    }
+    pub fn walk_recipe<'a, V: Visit<'a> + ?Sized>(v: &mut V, recipe: &'a Recipe) {
+        v.visit_operand(&recipe.root);
+    }
+    pub fn walk_operand<'a, V: Visit<'a> + ?Sized>(v: &mut V, op: &'a Operand) {
+        match op {
+            Operand::Ingredient { quantities, .. } => {
+                for quantity in quantities {
+                    v.visit_quantity(quantity);
+                }
+            }
+            Operand::UnaryOp { operand, .. } => v.visit_operand(operand),
+            Operand::BinaryOp { first, second, .. } => {
+                v.visit_operand(first);
+                v.visit_operand(second);
+            }
+            Operand::MissingOperand { .. } => {}
+            Operand::UnusedOperands { operands, .. } => {
+                for operand in operands {
+                    v.visit_operand(operand);
+                }
+            }
+        }
+    }
+
+    pub trait VisitMut {
+        fn visit_source_file_mut(&mut self, file: &mut SourceFile) {
+            walk_source_file_mut(self, file)
+        }
+        fn visit_recipe_mut(&mut self, recipe: &mut Recipe) {
+            walk_recipe_mut(self, recipe)
+        }
+        fn visit_operand_mut(&mut self, op: &mut Operand) {
+            walk_operand_mut(self, op)
+        }
+        fn visit_quantity_mut(&mut self, _quantity: &mut Quantity) {}
+    }
+
+    pub fn walk_source_file_mut<V: VisitMut + ?Sized>(v: &mut V, file: &mut SourceFile) {
+        for recipe in &mut file.recipes {
+            v.visit_recipe_mut(recipe);
+        }
+    }
+    pub fn walk_recipe_mut<V: VisitMut + ?Sized>(v: &mut V, recipe: &mut Recipe) {
+        v.visit_operand_mut(&mut recipe.root);
+    }
+    pub fn walk_operand_mut<V: VisitMut + ?Sized>(v: &mut V, op: &mut Operand) {
+        match op {
+            Operand::Ingredient { quantities, .. } => {
+                for quantity in quantities {
+                    v.visit_quantity_mut(quantity);
+                }
+            }
+            Operand::UnaryOp { operand, .. } => v.visit_operand_mut(operand),
+            Operand::BinaryOp { first, second, .. } => {
+                v.visit_operand_mut(first);
+                v.visit_operand_mut(second);
+            }
+            Operand::MissingOperand { .. } => {}
+            Operand::UnusedOperands { operands, .. } => {
+                for operand in operands {
+                    v.visit_operand_mut(operand);
+                }
+            }
+        }
+    }
+
+    pub trait Fold {
+        fn fold_source_file(&mut self, file: SourceFile) -> SourceFile {
+            fold_source_file(self, file)
+        }
+        fn fold_recipe(&mut self, recipe: Recipe) -> Recipe {
+            fold_recipe(self, recipe)
+        }
+        fn fold_operand(&mut self, op: Operand) -> Operand {
+            fold_operand(self, op)
+        }
+        fn fold_quantity(&mut self, quantity: Quantity) -> Quantity {
+            quantity
+        }
+    }
+
+    pub fn fold_source_file<F: Fold + ?Sized>(f: &mut F, file: SourceFile) -> SourceFile {
+        SourceFile {
+            recipes: file
+                .recipes
+                .into_iter()
+                .map(|recipe| f.fold_recipe(recipe))
+                .collect(),
+        }
+    }
+    pub fn fold_recipe<F: Fold + ?Sized>(f: &mut F, recipe: Recipe) -> Recipe {
+        Recipe {
+            root: f.fold_operand(recipe.root),
+            ..recipe
+        }
+    }
+    pub fn fold_operand<F: Fold + ?Sized>(f: &mut F, op: Operand) -> Operand {
+        match op {
+            Operand::Ingredient {
+                position,
+                derived,
+                quantities,
+                unit,
+                text,
+            } => Operand::Ingredient {
+                position,
+                derived,
+                quantities: quantities
+                    .into_iter()
+                    .map(|quantity| f.fold_quantity(quantity))
+                    .collect(),
+                unit,
+                text,
+            },
+            Operand::UnaryOp {
+                position,
+                operand,
+                text,
+            } => Operand::UnaryOp {
+                position,
+                operand: Box::new(f.fold_operand(*operand)),
+                text,
+            },
+            Operand::BinaryOp {
+                position,
+                first,
+                second,
+                text,
+            } => Operand::BinaryOp {
+                position,
+                first: Box::new(f.fold_operand(*first)),
+                second: Box::new(f.fold_operand(*second)),
+                text,
+            },
+            Operand::MissingOperand { position } => Operand::MissingOperand { position },
+            Operand::UnusedOperands { position, operands } => Operand::UnusedOperands {
+                position,
+                operands: operands
+                    .into_iter()
+                    .map(|operand| f.fold_operand(operand))
+                    .collect(),
+            },
+        }
+    }
+}
+
+/// Traversal of [`crate::semantic`] trees.
+pub mod semantic {
+    use crate::semantic::{Ingredient, Operand, Recipe, SourceFile};
+
+    pub trait Visit<'a> {
+        fn visit_source_file(&mut self, file: &'a SourceFile) {
+            walk_source_file(self, file)
+        }
+        fn visit_recipe(&mut self, recipe: &'a Recipe) {
+            walk_recipe(self, recipe)
+        }
+        fn visit_operand(&mut self, op: &'a Operand) {
+            walk_operand(self, op)
+        }
+        fn visit_ingredient(&mut self, _ingredient: &'a Ingredient) {}
+    }
+
+    pub fn walk_source_file<'a, V: Visit<'a> + ?Sized>(v: &mut V, file: &'a SourceFile) {
+        for recipe in &file.recipes {
+            v.visit_recipe(recipe);
+        }
+    }
+    pub fn walk_recipe<'a, V: Visit<'a> + ?Sized>(v: &mut V, recipe: &'a Recipe) {
+        v.visit_operand(&recipe.root);
+    }
+    pub fn walk_operand<'a, V: Visit<'a> + ?Sized>(v: &mut V, op: &'a Operand) {
+        match op {
+            Operand::Ingredient(ingredient) => v.visit_ingredient(ingredient),
+            Operand::Operator { operands, .. } | Operand::UnusedOperands { operands, .. } => {
+                for operand in operands {
+                    v.visit_operand(operand);
+                }
+            }
+            Operand::MissingOperand { .. } => {}
+        }
+    }
+
+    pub trait VisitMut {
+        fn visit_source_file_mut(&mut self, file: &mut SourceFile) {
+            walk_source_file_mut(self, file)
+        }
+        fn visit_recipe_mut(&mut self, recipe: &mut Recipe) {
+            walk_recipe_mut(self, recipe)
+        }
+        fn visit_operand_mut(&mut self, op: &mut Operand) {
+            walk_operand_mut(self, op)
+        }
+        fn visit_ingredient_mut(&mut self, _ingredient: &mut Ingredient) {}
+    }
+
+    pub fn walk_source_file_mut<V: VisitMut + ?Sized>(v: &mut V, file: &mut SourceFile) {
+        for recipe in &mut file.recipes {
+            v.visit_recipe_mut(recipe);
+        }
+    }
+    pub fn walk_recipe_mut<V: VisitMut + ?Sized>(v: &mut V, recipe: &mut Recipe) {
+        v.visit_operand_mut(&mut recipe.root);
+    }
+    pub fn walk_operand_mut<V: VisitMut + ?Sized>(v: &mut V, op: &mut Operand) {
+        match op {
+            Operand::Ingredient(ingredient) => v.visit_ingredient_mut(ingredient),
+            Operand::Operator { operands, .. } | Operand::UnusedOperands { operands, .. } => {
+                for operand in operands {
+                    v.visit_operand_mut(operand);
+                }
+            }
+            Operand::MissingOperand { .. } => {}
+        }
+    }
+
+    pub trait Fold {
+        fn fold_source_file(&mut self, file: SourceFile) -> SourceFile {
+            fold_source_file(self, file)
+        }
+        fn fold_recipe(&mut self, recipe: Recipe) -> Recipe {
+            fold_recipe(self, recipe)
+        }
+        fn fold_operand(&mut self, op: Operand) -> Operand {
+            fold_operand(self, op)
+        }
+        fn fold_ingredient(&mut self, ingredient: Ingredient) -> Ingredient {
+            ingredient
+        }
+    }
+
+    pub fn fold_source_file<F: Fold + ?Sized>(f: &mut F, file: SourceFile) -> SourceFile {
+        SourceFile {
+            recipes: file
+                .recipes
+                .into_iter()
+                .map(|recipe| f.fold_recipe(recipe))
+                .collect(),
+        }
+    }
+    pub fn fold_recipe<F: Fold + ?Sized>(f: &mut F, recipe: Recipe) -> Recipe {
+        Recipe {
+            root: f.fold_operand(recipe.root),
+            ..recipe
+        }
+    }
+    pub fn fold_operand<F: Fold + ?Sized>(f: &mut F, op: Operand) -> Operand {
+        match op {
+            Operand::Ingredient(ingredient) => Operand::Ingredient(f.fold_ingredient(ingredient)),
+            Operand::Operator {
+                position,
+                text,
+                operands,
+                annotations,
+            } => Operand::Operator {
+                position,
+                text,
+                operands: operands
+                    .into_iter()
+                    .map(|operand| f.fold_operand(operand))
+                    .collect(),
+                annotations,
+            },
+            Operand::MissingOperand { position } => Operand::MissingOperand { position },
+            Operand::UnusedOperands { position, operands } => Operand::UnusedOperands {
+                position,
+                operands: operands
+                    .into_iter()
+                    .map(|operand| f.fold_operand(operand))
+                    .collect(),
+            },
+        }
+    }
+}
@@ -1,7 +1,7 @@
 use std::fs;
 
 use expect_test::{expect_file, ExpectFile};
-use slop::{compile, format, parse, to_svgs};
+use slop::{compile, cst, format, parse, parse_cst, to_svgs};
 
 macro_rules! define_test {
     ($name:ident) => {
@@ -45,6 +45,12 @@ fn test(
     let actual_parse = parse(&src);
     expect_parse.assert_debug_eq(&actual_parse);
 
+    let (actual_cst, actual_cst_errors) = parse_cst(&src);
+    assert_eq!(actual_cst.to_string(), src);
+    if actual_cst_errors.0.is_empty() {
+        assert_eq!(cst::convert_source_file(&actual_cst), actual_parse.0);
+    }
+
     let (actual_format, actual_format_errors) = format(&src);
     expect_format.assert_eq(&actual_format);
     expect_format_errors.assert_debug_eq(&actual_format_errors);
@@ -0,0 +1,91 @@
+use expect_test::expect;
+use slop::{
+    annotation::{annotate, AnnotationKind},
+    compile,
+};
+
+fn describe(text: &str) -> String {
+    annotate(text)
+        .iter()
+        .map(|annotation| {
+            let value = match &annotation.kind {
+                AnnotationKind::Duration(duration) => format!("duration {duration}"),
+                AnnotationKind::Temperature(temperature) => format!("temperature {temperature}"),
+                AnnotationKind::Heat(heat) => format!("heat {heat}"),
+                _ => unreachable!(),
+            };
+            format!("{:?} {value}", &text[annotation.span.clone()])
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[test]
+fn annotate_text() {
+    let texts = [
+        "bake 375F 10m",
+        "steam for 2.5h",
+        "cover cook 5m on medium low heat",
+        "simmer 10-12 minutes over high heat",
+        "roast at 200 °C for 1 hour 30 minutes",
+        "preheat oven to 350 degrees",
+        "add 2 C of water and stir",
+        "saute 2 medium onions",
+    ];
+    let described: Vec<String> = texts.iter().map(|text| describe(text)).collect();
+    expect![[r#"
+        [
+            "\"375F\" temperature 375°F, \"10m\" duration 10m",
+            "\"2.5h\" duration 2h 30m",
+            "\"5m\" duration 5m, \"on medium low heat\" heat medium low",
+            "\"10-12 minutes\" duration 10m-12m, \"high heat\" heat high",
+            "\"200 °C\" temperature 200°C, \"1 hour 30 minutes\" duration 1h 30m",
+            "\"350 degrees\" temperature 350°F",
+            "",
+            "",
+        ]
+    "#]]
+    .assert_debug_eq(&described);
+}
+
+#[test]
+fn recipe_times() {
+    let (file, errors) = compile(
+        "<
+*2 cups: flour
+*1 cup: water #mix
+=knead 10m
+=let rise 1-2 hours
+=bake 425F 30-35 min
+>",
+    );
+    assert!(errors.is_empty(), "{errors}");
+    let times = file.recipes[0].times();
+    assert_eq!(times.active.to_string(), "10m");
+    assert_eq!(times.passive.to_string(), "1h 30m-2h 35m");
+    assert_eq!(times.total().to_string(), "1h 40m-2h 45m");
+}
+
+#[test]
+fn annotation_source_spans() {
+    let src = "<** Bread\n*2 cups: flour\n*1 cup: water #  mix 2m\n=bake 425F 30 min\n>";
+    let (file, errors) = compile(src);
+    assert!(errors.is_empty(), "{errors}");
+    let mut spans = Vec::new();
+    let mut operand = &file.recipes[0].root;
+    while let Some(text) = operand.text() {
+        for annotation in operand.annotations() {
+            let span = annotation.source_span(src, operand).unwrap();
+            spans.push(format!("{text}: {}", &src[span]));
+        }
+        let Some(first) = operand.operands().first() else {
+            break;
+        };
+        operand = first;
+    }
+    expect![[r#"
+        bake 425F 30 min: 425F
+        bake 425F 30 min: 30 min
+        mix 2m: 2m"#]]
+    .assert_eq(&spans.join("\n"));
+}
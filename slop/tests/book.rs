@@ -0,0 +1,224 @@
+use std::path::Path;
+
+use expect_test::expect;
+use slop::{
+    book::{self, Book, Chapter, Manifest},
+    compile,
+};
+
+fn load(manifest: &Manifest) -> Book {
+    let chapters = manifest
+        .chapters
+        .iter()
+        .map(|chapter| {
+            let recipes = chapter
+                .files
+                .iter()
+                .flat_map(|path| {
+                    let (file, errors) = compile(&std::fs::read_to_string(path).unwrap());
+                    assert!(errors.0.is_empty(), "{errors}");
+                    file.recipes
+                })
+                .collect();
+            Chapter {
+                title: chapter.title.clone(),
+                recipes,
+            }
+        })
+        .collect();
+    Book {
+        title: manifest.title.clone().unwrap_or_default(),
+        chapters,
+    }
+}
+
+#[test]
+fn manifest_from_toml() {
+    let manifest = Manifest::load(Path::new("./tests/book/cookbook.toml")).unwrap();
+    expect![[r#"
+        Manifest {
+            title: Some(
+                "Weekend Breakfasts",
+            ),
+            chapters: [
+                ManifestChapter {
+                    title: Some(
+                        "Eggs",
+                    ),
+                    files: [
+                        "./tests/book/breakfast/scrambled_eggs.slop",
+                    ],
+                },
+                ManifestChapter {
+                    title: None,
+                    files: [
+                        "./tests/book/breakfast/pancakes.slop",
+                        "./tests/book/breakfast/scrambled_eggs.slop",
+                        "./tests/book/toast.slop",
+                    ],
+                },
+            ],
+        }"#]]
+    .assert_eq(&format!("{manifest:#?}"));
+
+    let errors: Vec<String> = [
+        "title = 1",
+        "chapters = 1",
+        "[[chapters]]\ntitle = \"Eggs\"",
+        "[[chapters]]\nrecipes = [\"a.slop\"]\n[[chapters]]\nrecipes = [1]",
+    ]
+    .iter()
+    .map(|src| Manifest::from_toml(src).unwrap_err().to_string())
+    .collect();
+    expect![[r#"
+        `title` must be a string
+        invalid chapter 1, expected a table with a `recipes` list of paths and an optional `title`
+        invalid chapter 1, expected a table with a `recipes` list of paths and an optional `title`
+        invalid chapter 2, expected a table with a `recipes` list of paths and an optional `title`"#]]
+    .assert_eq(&errors.join("\n"));
+}
+
+#[test]
+fn manifest_of_directory() {
+    let manifest = Manifest::discover(Path::new("./tests/book")).unwrap();
+    let chapters: Vec<String> = manifest
+        .chapters
+        .iter()
+        .map(|chapter| {
+            let files: Vec<String> = chapter
+                .files
+                .iter()
+                .map(|path| path.display().to_string())
+                .collect();
+            format!("{:?}: {}", chapter.title, files.join(", "))
+        })
+        .collect();
+    assert_eq!(manifest.title.as_deref(), Some("Book"));
+    expect![[r#"
+        None: ./tests/book/toast.slop
+        Some("Breakfast"): ./tests/book/breakfast/pancakes.slop, ./tests/book/breakfast/scrambled_eggs.slop"#]]
+    .assert_eq(&chapters.join("\n"));
+}
+
+#[test]
+fn ingredient_index() {
+    let book = load(&Manifest::discover(Path::new("./tests/book")).unwrap());
+    let titles: Vec<&str> = book.recipes().map(|r| r.title().unwrap()).collect();
+    let index: Vec<String> = book
+        .index()
+        .into_iter()
+        .map(|(ingredient, recipes)| {
+            let recipes: Vec<&str> = recipes.into_iter().map(|n| titles[n]).collect();
+            format!("{ingredient}: {}", recipes.join(", "))
+        })
+        .collect();
+    expect![[r#"
+        bread: Toast
+        butter: Scrambled Eggs
+        eggs: Pancakes, Scrambled Eggs
+        flour: Pancakes
+        milk: Pancakes"#]]
+    .assert_eq(&index.join("\n"));
+}
+
+#[test]
+fn book_to_html() {
+    let (toast, errors) = compile(&std::fs::read_to_string("./tests/book/toast.slop").unwrap());
+    assert!(errors.0.is_empty(), "{errors}");
+    let book = Book {
+        title: "Toast & Co".to_string(),
+        chapters: vec![Chapter {
+            title: Some("Breakfast".to_string()),
+            recipes: toast.recipes,
+        }],
+    };
+    expect![[r##"
+        <!DOCTYPE html>
+        <html lang="en">
+        <head>
+        <meta charset="utf-8">
+        <title>Toast &amp; Co</title>
+        <style>
+        body { font-family: sans-serif; max-width: 60em; margin: 0 auto; padding: 1em; }
+        table { border-collapse: collapse; margin: 1em 0; }
+        caption { font-weight: bold; text-align: left; }
+        th, td { border: 1px solid #444; padding: 0.25em 0.5em; text-align: left; }
+        .ingredients td:first-child { text-align: right; }
+        .card th { font-weight: normal; }
+        .card td { background: #eee; }
+        .card .derived { font-style: italic; }
+        @media print { .recipe { break-inside: avoid; } }
+        .title-page { text-align: center; padding-top: 30vh; }
+        .contents ol { list-style: none; }
+        .index dd { margin: 0 0 0.5em 2em; }
+        .card { break-inside: avoid; }
+        @page { size: A4; margin: 2cm; }
+        @media print { .contents, .chapter, .index { break-before: page; } a { color: inherit; text-decoration: none; } }
+        </style>
+        </head>
+        <body>
+        <header class="title-page">
+        <h1>Toast &amp; Co</h1>
+        </header>
+        <nav class="contents">
+        <h1>Contents</h1>
+        <ol>
+        <li><a href="#chapter-1">Breakfast</a>
+        <ol>
+        <li><a href="#recipe-1">Toast</a></li>
+        </ol>
+        </li>
+        </ol>
+        </nav>
+        <section class="chapter" id="chapter-1">
+        <h1>Breakfast</h1>
+        <article class="recipe" id="recipe-1">
+        <h1>Toast</h1>
+        <table class="ingredients">
+        <caption>Ingredients</caption>
+        <thead>
+        <tr><th scope="col">Quantity</th><th scope="col">Unit</th><th scope="col">Ingredient</th></tr>
+        </thead>
+        <tbody>
+        <tr><td>1</td><td>slice</td><td>bread</td></tr>
+        </tbody>
+        </table>
+        <h2>Instructions</h2>
+        <ol>
+        <li>Toast the bread.</li>
+        </ol>
+        <table class="card">
+        <caption>Recipe card</caption>
+        <tbody>
+        <tr><th scope="row" id="recipe-1-cell-1">1 slice bread</th><td id="recipe-1-cell-2" headers="recipe-1-cell-1">toast</td></tr>
+        </tbody>
+        </table>
+        </article>
+        </section>
+        <section class="index">
+        <h1>Index of ingredients</h1>
+        <dl>
+        <dt>bread</dt>
+        <dd><a href="#recipe-1">Toast</a></dd>
+        </dl>
+        </section>
+        </body>
+        </html>
+    "##]]
+    .assert_eq(&book::to_html(&book));
+}
+
+#[cfg(feature = "pdf")]
+#[test]
+fn book_to_pdf() {
+    let book = load(&Manifest::load(Path::new("./tests/book/cookbook.toml")).unwrap());
+    let pdf = book::pdf::to_pdf(&book);
+    let pdf = String::from_utf8_lossy(&pdf);
+    assert!(pdf.starts_with("%PDF-"));
+    // A title page, the contents, a page per recipe and the index.
+    assert_eq!(pdf.matches("/Type /Page\n").count(), 7);
+    assert!(pdf.contains("(Weekend Breakfasts) Tj"));
+    // Pancakes are on the fourth page, after the title, the contents and the first chapter.
+    assert!(pdf.contains("(eggs, 3, 4, 5) Tj"));
+    assert!(pdf.contains("(fry 5m) Tj"));
+}
@@ -0,0 +1,78 @@
+use std::fs;
+
+use expect_test::{expect_file, ExpectFile};
+use slop::{compile, cooklang::to_cooklang, import, SourceFile};
+
+// Cooklang files are imported, exported again and imported back, the ingredients must survive
+// the round trip.
+macro_rules! define_import_test {
+    ($name:ident) => {
+        #[test]
+        fn $name() {
+            test_import(
+                concat!("./tests/cooklang/", stringify!($name), ".cook"),
+                expect_file![concat!("cooklang/expected/", stringify!($name), ".slop")],
+                expect_file![concat!("cooklang/expected/", stringify!($name), ".cook")],
+            )
+        }
+    };
+}
+
+// Slop recipes are exported and imported back.
+macro_rules! define_export_test {
+    ($name:ident) => {
+        #[test]
+        fn $name() {
+            test_export(
+                concat!("./tests/recipes/", stringify!($name), ".slop"),
+                expect_file![concat!("cooklang/expected/", stringify!($name), ".cook")],
+            )
+        }
+    };
+}
+
+fn test_import(fpath: &str, expect_import: ExpectFile, expect_export: ExpectFile) {
+    let src = fs::read_to_string(fpath).unwrap();
+    let imported = import::cooklang::import(&src).unwrap();
+    expect_import.assert_eq(&imported);
+    let file = compile_ok(&imported);
+    let exported = to_cooklang(&file.recipes()[0]);
+    expect_export.assert_eq(&exported);
+    let reimported = compile_ok(&import::cooklang::import(&exported).unwrap());
+    assert_eq!(ingredients(&reimported), ingredients(&file));
+}
+
+fn test_export(fpath: &str, expect_export: ExpectFile) {
+    let src = fs::read_to_string(fpath).unwrap();
+    let file = compile_ok(&src);
+    let exported = to_cooklang(&file.recipes()[0]);
+    expect_export.assert_eq(&exported);
+    let reimported = compile_ok(&import::cooklang::import(&exported).unwrap());
+    assert_eq!(ingredients(&reimported), ingredients(&file));
+}
+
+fn compile_ok(src: &str) -> SourceFile {
+    let (file, errors) = compile(src);
+    assert!(errors.0.is_empty(), "{errors}\n{src}");
+    file
+}
+
+// The raw ingredients, sorted as linearising the tree can change their order.
+fn ingredients(file: &SourceFile) -> Vec<String> {
+    let mut ingredients: Vec<String> = file.recipes()[0]
+        .ingredients()
+        .into_iter()
+        .filter(|ingredient| !ingredient.is_derived())
+        .map(ToString::to_string)
+        .collect();
+    ingredients.sort();
+    ingredients
+}
+
+define_import_test!(guacamole);
+define_import_test!(pancakes);
+define_import_test!(tea);
+
+define_export_test!(cookies);
+define_export_test!(hauloumi);
+define_export_test!(souffle);
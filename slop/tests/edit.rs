@@ -0,0 +1,184 @@
+use std::fs;
+
+use expect_test::expect;
+use slop::{
+    compile,
+    edit::{self, derived_ingredient, ingredient},
+    semantic::{Ingredient, Operand, Recipe, SourceFile},
+    visit::semantic::{walk_operand_mut, walk_recipe_mut, VisitMut},
+};
+
+struct ClearPositions;
+
+impl VisitMut for ClearPositions {
+    fn visit_recipe_mut(&mut self, recipe: &mut Recipe) {
+        recipe.position = Default::default();
+        walk_recipe_mut(self, recipe)
+    }
+    fn visit_operand_mut(&mut self, op: &mut Operand) {
+        match op {
+            Operand::Operator { position, .. }
+            | Operand::MissingOperand { position }
+            | Operand::UnusedOperands { position, .. } => *position = Default::default(),
+            _ => {}
+        }
+        walk_operand_mut(self, op)
+    }
+    fn visit_ingredient_mut(&mut self, ingredient: &mut Ingredient) {
+        ingredient.position = Default::default();
+    }
+}
+
+fn without_positions(mut file: SourceFile) -> SourceFile {
+    ClearPositions.visit_source_file_mut(&mut file);
+    file
+}
+
+#[test]
+fn to_source_round_trip() {
+    for entry in fs::read_dir("./tests/recipes").unwrap() {
+        let path = entry.unwrap().path();
+        if path.extension().and_then(|ext| ext.to_str()) != Some("slop") {
+            continue;
+        }
+        let (file, errors) = compile(&fs::read_to_string(&path).unwrap());
+        if !errors.0.is_empty() {
+            continue;
+        }
+        let src = edit::to_source(&file).unwrap();
+        let (regenerated, errors) = compile(&src);
+        assert!(errors.0.is_empty(), "{}: {errors}", path.display());
+        assert_eq!(
+            without_positions(regenerated),
+            without_positions(file),
+            "{}",
+            path.display()
+        );
+    }
+}
+
+#[test]
+fn edit_recipe() {
+    let (mut file, _) = compile(&fs::read_to_string("./tests/recipes/simple.slop").unwrap());
+    let root = &mut file.recipes[0].root;
+    edit::insert_ingredient(
+        root,
+        &[],
+        1,
+        ingredient(Some("1/2"), Some("tsp"), "salt").unwrap(),
+    )
+    .unwrap();
+    edit::wrap_unary(root, &[2], "shred").unwrap();
+    edit::move_subtree(root, &[0, 1], &[], 0).unwrap();
+    edit::delete(root, &[1, 0, 0, 0, 0]).unwrap();
+    edit::wrap_binary(
+        root,
+        &[1, 0, 0, 0, 0],
+        Operand::Ingredient(ingredient(Some("1"), Some("tbsp"), "oil").unwrap()),
+        "+",
+    )
+    .unwrap();
+
+    let src = edit::to_source(&file).unwrap();
+    expect![[r#"
+        <
+        *1/3 cup: milk
+        *2 cups: macarroni noodles
+        *1 tbsp: oil #+ =boil till soft =drain
+        *1/4 cup: butter #stir until melted =stir #+
+        *1/2 tsp: salt #+
+        *1 pouch: dried cheese =shred #stir until well mixed
+        >
+    "#]]
+    .assert_eq(&src);
+    let (compiled, errors) = compile(&src);
+    assert!(errors.0.is_empty(), "{errors}");
+    assert_eq!(without_positions(compiled), without_positions(file));
+}
+
+#[test]
+fn delete_operands() {
+    let (mut file, errors) = compile("<** Batter\n*1 cup: flour\n*2: eggs #whisk =rest 30m\n>");
+    assert!(errors.is_empty(), "{errors}");
+    let root = &mut file.recipes[0].root;
+    // The binary whisk becomes a unary of the flour.
+    edit::delete(root, &[0, 1]).unwrap();
+    edit::insert_ingredient(
+        root,
+        &[],
+        1,
+        derived_ingredient(Some("1"), Some("cup"), "whey").unwrap(),
+    )
+    .unwrap();
+    expect![[r#"
+        <** Batter
+        *1 cup: flour =whisk
+        *^1 cup: whey #rest 30m
+        >
+    "#]]
+    .assert_eq(&edit::to_source(&file).unwrap());
+
+    // The only operand of an operator leaves a missing operand.
+    let root = &mut file.recipes[0].root;
+    edit::delete(root, &[0, 0]).unwrap();
+    assert!(matches!(
+        edit::get(root, &[0, 0]),
+        Some(Operand::MissingOperand { .. })
+    ));
+    assert!(edit::delete(root, &[0, 1]).is_err());
+
+    // One of two unused operands leaves the other as the root.
+    let (mut file, _) = compile("<** Batter\n*1 cup: flour\n*2: eggs #whisk\n*salt\n>");
+    let root = &mut file.recipes[0].root;
+    edit::delete(root, &[1]).unwrap();
+    assert!(matches!(root, Operand::Operator { .. }));
+    expect![[r#"
+        <** Batter
+        *1 cup: flour
+        *2: eggs #whisk
+        >
+    "#]]
+    .assert_eq(&edit::to_source(&file).unwrap());
+}
+
+#[test]
+fn build_recipe() {
+    let root = edit::operator(
+        "toast 2m",
+        vec![Operand::Ingredient(
+            ingredient(Some("2"), Some("slices"), "bread").unwrap(),
+        )],
+    );
+    let root = edit::operator(
+        "spread",
+        vec![
+            root,
+            Operand::Ingredient(ingredient(Some("1"), Some("tbsp"), "butter").unwrap()),
+        ],
+    );
+    let file = edit::source_file(vec![edit::recipe(
+        Some("Toast"),
+        Some("Crisp and buttery."),
+        Some("Serves 1"),
+        root,
+    )]);
+    let src = edit::to_source(&file).unwrap();
+    expect![[r#"
+        <** Toast ## Crisp and buttery.
+        *2 slices: bread =toast 2m
+        *1 tbsp: butter #spread
+        #*Serves 1
+        >
+    "#]]
+    .assert_eq(&src);
+    let (compiled, errors) = compile(&src);
+    assert!(errors.is_empty(), "{errors}");
+    assert_eq!(without_positions(compiled), file);
+
+    // Text that would read back differently from the source is rejected.
+    for text in [" salt", "salt ", "salt\npepper", "2 eggs", "salt: to taste"] {
+        let ingredient = Operand::Ingredient(ingredient(None, None, text).unwrap());
+        let file = edit::source_file(vec![edit::recipe(None, None, None, ingredient)]);
+        assert!(edit::to_source(&file).is_err(), "{text:?}");
+    }
+}
@@ -0,0 +1,83 @@
+use expect_test::expect;
+use slop::{
+    compile,
+    config::Config,
+    equipment::{checklist, conflicts, Vocabulary},
+    menu::schedule,
+};
+
+#[test]
+fn detect_equipment() {
+    let vocabulary = Vocabulary::default();
+    let names = |text: &str| -> Vec<String> {
+        vocabulary
+            .detect(text)
+            .iter()
+            .map(|e| e.name.clone())
+            .collect()
+    };
+    assert_eq!(names("heat in pan 1m"), ["pan"]);
+    assert_eq!(
+        names("spread on a sheet pan, bake 400F"),
+        ["baking sheet", "oven"]
+    );
+    assert_eq!(names("melt in a double boiler"), ["double boiler"]);
+    assert_eq!(names("Blend until smooth"), ["blender"]);
+    assert!(names("panko crumbs").is_empty());
+}
+
+#[test]
+fn configured_vocabulary() {
+    let config = Config::from_toml(
+        r#"
+[equipment]
+wok = ["wok", "stir fry"]
+oven = { words = ["oven"], shared = false }
+"#,
+    )
+    .unwrap();
+    let (file, errors) = compile("<** Stir fry\n*1 cup: rice =bake\n*1: pepper =stir fry 3m #+\n>");
+    assert!(errors.is_empty(), "{errors}");
+    let names: Vec<&str> = checklist(&file.recipes[0], &config.equipment)
+        .iter()
+        .map(|e| e.name.as_str())
+        .collect();
+    assert_eq!(names, ["wok"]);
+    let error = Config::from_toml("[equipment]\nwok = 1").unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "invalid equipment `wok`, expected a list of words or a table with `words` and `shared`"
+    );
+}
+
+#[test]
+fn menu_conflicts() {
+    let (file, errors) = compile(
+        "<** Roast
+*1: chicken =roast 400F 1h
+>
+<** Cookies
+*1 cup: dough =bake 350F 12m
+>
+<** Soup
+*1 qt: stock =simmer 30m
+>
+<** Sauce
+*1 cup: cream =simmer 10m
+>
+",
+    );
+    assert!(errors.is_empty(), "{errors}");
+    let plan = schedule(file.recipes(), "19:00".parse().unwrap());
+    let conflicts: Vec<String> = conflicts(&plan, &Vocabulary::default())
+        .iter()
+        .map(|c| c.to_string())
+        .collect();
+    expect![[r#"
+        [
+            "Roast `roast 400F 1h` (18:00-19:00) and Cookies `bake 350F 12m` (18:48-19:00) need the oven at 400°F and 350°F",
+            "Soup `simmer 30m` (18:30-19:00) and Sauce `simmer 10m` (18:50-19:00) both need the pot",
+        ]
+    "#]]
+    .assert_debug_eq(&conflicts);
+}
@@ -0,0 +1,91 @@
+use expect_test::expect;
+use slop::{
+    compile,
+    diagnostic::{Fix, TextEdit},
+    fix,
+    lint::LintConfig,
+    Errors,
+};
+
+#[test]
+fn fix_source() {
+    let src = "<** Breakfast
+*2: eggs =scramble
+*1 lb: bacon =fry
+*1 slice: toast
+#serve
+>
+<** Soup
+*1 cup: broth #simmer
+>
+";
+    let (fixed, diagnostics) = fix::fix(src, &LintConfig::default());
+    expect![[r#"
+        <** Breakfast
+        *2: eggs =scramble
+        *1 lbs: bacon =fry
+        *1 slice: toast
+        #serve #+
+        >
+        <** Soup
+        *1 cup: broth *ingredient #simmer
+        >
+    "#]]
+    .assert_eq(&fixed);
+    assert!(Errors::from(diagnostics).is_empty());
+    let (_, errors) = compile(&fixed);
+    assert!(errors.is_empty(), "{errors}");
+}
+
+#[test]
+fn suggest_fixes() {
+    let src = "<\n*1 pound: flour\n*sugar =sift\n>";
+    let (file, errors) = compile(src);
+    let (syntax, _) = slop::parse(src);
+    let mut diagnostics: Vec<_> = errors
+        .into_iter()
+        .chain(slop::lint::lint(&syntax, &file, &LintConfig::default()))
+        .collect();
+    fix::suggest(src, &file, &mut diagnostics);
+    let fixes: Vec<_> = diagnostics
+        .iter()
+        .flat_map(|d| &d.fixes)
+        .map(|fix| &fix.edits)
+        .collect();
+    expect![[r#"
+        [
+            [
+                TextEdit {
+                    span: 30..30,
+                    text: " #+",
+                },
+            ],
+            [
+                TextEdit {
+                    span: 5..10,
+                    text: "lbs",
+                },
+            ],
+        ]
+    "#]]
+    .assert_debug_eq(&fixes);
+}
+
+#[test]
+fn apply_skips_conflicting_fixes() {
+    let fix = |start, end, text: &str| Fix {
+        message: String::new(),
+        edits: vec![TextEdit {
+            span: start..end,
+            text: text.to_string(),
+        }],
+    };
+    let fixes = [fix(0, 3, "one"), fix(2, 5, "two"), fix(7, 7, "three ")];
+    assert_eq!(fix::apply("abcdef ghi", &fixes), "onedef three ghi");
+}
+
+#[test]
+fn fix_misspelled_unit() {
+    let (fixed, _) = fix::fix("<\n*1 tbps: salt =sprinkle\n>", &LintConfig::default());
+    assert_eq!(fixed, "<\n*1 tbsp: salt =sprinkle\n>");
+}
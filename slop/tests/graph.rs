@@ -0,0 +1,81 @@
+use expect_test::expect;
+use slop::{compile, graph};
+
+const SOURCE: &str = "<** Butter & \"Buttermilk\"
+*1 pint: cream =whip 10m =drain
+*1 tsp: salt #knead
+*^buttermilk #serve
+>";
+
+#[test]
+fn recipe_to_dot() {
+    let (file, errors) = compile(SOURCE);
+    assert!(errors.0.is_empty(), "{errors}");
+    expect![[r#"
+        digraph "Butter & \"Buttermilk\"" {
+            rankdir=LR;
+            node [fontname="sans-serif"];
+            n1 [label="serve", shape=ellipse];
+            n2 [label="knead", shape=ellipse];
+            n3 [label="drain", shape=ellipse];
+            n4 [label="whip 10m", shape=ellipse];
+            n5 [label="1 pint cream", shape=box];
+            n6 [label="1 tsp salt", shape=box];
+            n7 [label="buttermilk", shape=box, style=dashed];
+            n5 -> n4;
+            n4 -> n3;
+            n3 -> n2;
+            n6 -> n2;
+            n2 -> n1;
+            n7 -> n1 [style=dashed];
+        }
+    "#]]
+    .assert_eq(&graph::to_dot(&file.recipes()[0]));
+}
+
+#[test]
+fn recipe_to_mermaid() {
+    let (file, errors) = compile(SOURCE);
+    assert!(errors.0.is_empty(), "{errors}");
+    expect![[r#"
+        ---
+        title: "Butter & \"Buttermilk\""
+        ---
+        flowchart LR
+            n1("serve")
+            n2("knead")
+            n3("drain")
+            n4("whip 10m")
+            n5["1 pint cream"]
+            n6["1 tsp salt"]
+            n7["buttermilk"]
+            n5 --> n4
+            n4 --> n3
+            n3 --> n2
+            n6 --> n2
+            n2 --> n1
+            n7 -.-> n1
+            classDef derived stroke-dasharray: 4 4
+            class n7 derived
+    "#]]
+    .assert_eq(&graph::to_mermaid(&file.recipes()[0]));
+}
+
+#[test]
+fn errors_in_graph() {
+    let (file, errors) = compile("<*1 cup: flour #+ *1: egg >");
+    assert!(!errors.0.is_empty());
+    expect![[r#"
+        flowchart LR
+            n1{{"unused operands"}}
+            n2("combine")
+            n3{{"missing operand"}}
+            n4["1 cup flour"]
+            n5["1 egg"]
+            n3 --> n2
+            n4 --> n2
+            n2 --> n1
+            n5 --> n1
+    "#]]
+    .assert_eq(&graph::to_mermaid(&file.recipes()[0]));
+}
@@ -0,0 +1,115 @@
+use expect_test::expect;
+use slop::{compile, html};
+
+const SOURCE: &str = "<** Butter & Buttermilk
+*1 pint: cream =whip 10m =drain
+*1 tsp: salt #knead
+*^buttermilk #serve
+>";
+
+#[test]
+fn recipe_card() {
+    let (file, errors) = compile(SOURCE);
+    assert!(errors.0.is_empty(), "{errors}");
+    expect![[r#"
+        <table class="card">
+        <caption>Recipe card</caption>
+        <tbody>
+        <tr><th scope="row" id="butter-cell-1">1 pint cream</th><td id="butter-cell-2" headers="butter-cell-1">whip 10m</td><td id="butter-cell-3" headers="butter-cell-1">drain</td><td id="butter-cell-5" rowspan="2" headers="butter-cell-1 butter-cell-4">knead</td><td id="butter-cell-7" rowspan="3" headers="butter-cell-1 butter-cell-4 butter-cell-6">serve</td></tr>
+        <tr><th scope="row" id="butter-cell-4" colspan="3">1 tsp salt</th></tr>
+        <tr><th scope="row" id="butter-cell-6" colspan="4" class="derived">buttermilk</th></tr>
+        </tbody>
+        </table>
+    "#]]
+    .assert_eq(&html::card(&file.recipes()[0], "butter"));
+}
+
+#[test]
+fn recipe_to_html() {
+    let (file, errors) = compile(SOURCE);
+    assert!(errors.0.is_empty(), "{errors}");
+    expect![[r#"
+        <!DOCTYPE html>
+        <html lang="en">
+        <head>
+        <meta charset="utf-8">
+        <title>Butter &amp; Buttermilk</title>
+        <style>
+        body { font-family: sans-serif; max-width: 60em; margin: 0 auto; padding: 1em; }
+        table { border-collapse: collapse; margin: 1em 0; }
+        caption { font-weight: bold; text-align: left; }
+        th, td { border: 1px solid #444; padding: 0.25em 0.5em; text-align: left; }
+        .ingredients td:first-child { text-align: right; }
+        .card th { font-weight: normal; }
+        .card td { background: #eee; }
+        .card .derived { font-style: italic; }
+        @media print { .recipe { break-inside: avoid; } }
+        </style>
+        </head>
+        <body>
+        <article class="recipe" id="recipe-1">
+        <h1>Butter &amp; Buttermilk</h1>
+        <table class="ingredients">
+        <caption>Ingredients</caption>
+        <thead>
+        <tr><th scope="col">Quantity</th><th scope="col">Unit</th><th scope="col">Ingredient</th></tr>
+        </thead>
+        <tbody>
+        <tr><td>1</td><td>pint</td><td>cream</td></tr>
+        <tr><td>1</td><td>tsp</td><td>salt</td></tr>
+        </tbody>
+        </table>
+        <h2>Instructions</h2>
+        <ol>
+        <li>Whip the cream 10m.</li>
+        <li>Drain.</li>
+        <li>Knead the salt.</li>
+        <li>Serve the buttermilk.</li>
+        </ol>
+        <table class="card">
+        <caption>Recipe card</caption>
+        <tbody>
+        <tr><th scope="row" id="recipe-1-cell-1">1 pint cream</th><td id="recipe-1-cell-2" headers="recipe-1-cell-1">whip 10m</td><td id="recipe-1-cell-3" headers="recipe-1-cell-1">drain</td><td id="recipe-1-cell-5" rowspan="2" headers="recipe-1-cell-1 recipe-1-cell-4">knead</td><td id="recipe-1-cell-7" rowspan="3" headers="recipe-1-cell-1 recipe-1-cell-4 recipe-1-cell-6">serve</td></tr>
+        <tr><th scope="row" id="recipe-1-cell-4" colspan="3">1 tsp salt</th></tr>
+        <tr><th scope="row" id="recipe-1-cell-6" colspan="4" class="derived">buttermilk</th></tr>
+        </tbody>
+        </table>
+        </article>
+        </body>
+        </html>
+    "#]]
+    .assert_eq(&html::to_html(file.recipes()));
+}
+
+#[test]
+fn escape_article() {
+    let (file, errors) =
+        compile("<** Mac & cheese\n*1 cup: macaroni =boil until \"al dente\" & drain\n>");
+    assert!(errors.is_empty(), "{errors}");
+    let article = html::article(&file.recipes()[0], "mac<&>");
+    expect![[r#"
+        <article class="recipe" id="mac&lt;&amp;&gt;">
+        <h1>Mac &amp; cheese</h1>
+        <table class="ingredients">
+        <caption>Ingredients</caption>
+        <thead>
+        <tr><th scope="col">Quantity</th><th scope="col">Unit</th><th scope="col">Ingredient</th></tr>
+        </thead>
+        <tbody>
+        <tr><td>1</td><td>cup</td><td>macaroni</td></tr>
+        </tbody>
+        </table>
+        <h2>Instructions</h2>
+        <ol>
+        <li>Boil the macaroni until &quot;al dente&quot; &amp; drain.</li>
+        </ol>
+        <table class="card">
+        <caption>Recipe card</caption>
+        <tbody>
+        <tr><th scope="row" id="mac&lt;&amp;&gt;-cell-1">1 cup macaroni</th><td id="mac&lt;&amp;&gt;-cell-2" headers="mac&lt;&amp;&gt;-cell-1">boil until &quot;al dente&quot; &amp; drain</td></tr>
+        </tbody>
+        </table>
+        </article>
+    "#]]
+    .assert_eq(&article);
+}
@@ -0,0 +1,116 @@
+use expect_test::expect;
+use slop::{
+    compile,
+    ical::{to_ical, Date},
+    menu::schedule,
+};
+
+#[test]
+fn menu_to_ical() {
+    let (file, errors) = compile(
+        "<** Overnight country loaf with a crisp crust and an airy open crumb
+*500 g: flour
+*350 g: water #mix, knead 10m
+=rise overnight 10h
+=bake 230C 40m
+>
+",
+    );
+    assert!(errors.is_empty(), "{errors}");
+    let plan = schedule(file.recipes(), "8:00".parse().unwrap());
+    let date: Date = "2024-03-01".parse().unwrap();
+    let ical = to_ical(&plan, date, true);
+    assert!(ical
+        .split("\r\n")
+        .all(|line| line.len() <= 75 && !line.contains('\n')));
+    expect![[r#"
+        BEGIN:VCALENDAR
+        VERSION:2.0
+        PRODID:-//slop//slop//EN
+        CALSCALE:GREGORIAN
+        BEGIN:VEVENT
+        UID:20240301T080000-4cdc356994bec74e-0@slop
+        DTSTAMP:20240301T000000Z
+        DTSTART:20240229T211000
+        DTEND:20240229T212000
+        SUMMARY:Overnight country loaf with a crisp crust and an airy open crumb: m
+         ix\, knead 10m
+        DESCRIPTION:500 g flour\n350 g water
+        BEGIN:VALARM
+        ACTION:DISPLAY
+        TRIGGER:PT0S
+        DESCRIPTION:Overnight country loaf with a crisp crust and an airy open crum
+         b: mix\, knead 10m
+        END:VALARM
+        END:VEVENT
+        BEGIN:VEVENT
+        UID:20240301T080000-4cdc356994bec74e-1@slop
+        DTSTAMP:20240301T000000Z
+        DTSTART:20240229T212000
+        DTEND:20240301T072000
+        SUMMARY:Overnight country loaf with a crisp crust and an airy open crumb: r
+         ise overnight 10h
+        TRANSP:TRANSPARENT
+        BEGIN:VALARM
+        ACTION:DISPLAY
+        TRIGGER:PT0S
+        DESCRIPTION:Overnight country loaf with a crisp crust and an airy open crum
+         b: rise overnight 10h
+        END:VALARM
+        END:VEVENT
+        BEGIN:VEVENT
+        UID:20240301T080000-4cdc356994bec74e-2@slop
+        DTSTAMP:20240301T000000Z
+        DTSTART:20240301T072000
+        DTEND:20240301T080000
+        SUMMARY:Overnight country loaf with a crisp crust and an airy open crumb: b
+         ake 230C 40m
+        TRANSP:TRANSPARENT
+        BEGIN:VALARM
+        ACTION:DISPLAY
+        TRIGGER:PT0S
+        DESCRIPTION:Overnight country loaf with a crisp crust and an airy open crum
+         b: bake 230C 40m
+        END:VALARM
+        END:VEVENT
+        END:VCALENDAR
+    "#]]
+    .assert_eq(&ical.replace('\r', ""));
+
+    // Another menu served at the same time has other UIDs.
+    let (file, errors) = compile("<** Toast\n*1 slice: bread =toast 2m\n>");
+    assert!(errors.is_empty(), "{errors}");
+    let plan = schedule(file.recipes(), "8:00".parse().unwrap());
+    let other = to_ical(&plan, date, true);
+    let uid = |ical: &str| {
+        ical.lines()
+            .find(|line| line.starts_with("UID:"))
+            .map(str::to_string)
+    };
+    assert_ne!(uid(&ical), uid(&other));
+}
+
+#[test]
+fn escape_line_breaks() {
+    // Windows and old Mac line breaks within an operator text.
+    let (file, errors) =
+        compile("<** Toast\r\n*1 slice: bread =toast\r\nlightly\rthen butter 2m\r\n>");
+    assert!(errors.is_empty(), "{errors}");
+    let plan = schedule(file.recipes(), "8:00".parse().unwrap());
+    let ical = to_ical(&plan, "2024-03-01".parse().unwrap(), true);
+    assert!(ical.split("\r\n").all(|line| !line.contains(['\r', '\n'])));
+    assert!(
+        ical.contains("SUMMARY:Toast: toast\\nlightly\\nthen butter 2m\r\n"),
+        "{ical}"
+    );
+}
+
+#[test]
+fn parse_date() {
+    let date: Date = "2024-02-28".parse().unwrap();
+    assert_eq!(date.add_days(1).to_string(), "2024-02-29");
+    assert_eq!(date.add_days(-59).to_string(), "2023-12-31");
+    assert!("2023-02-29".parse::<Date>().is_err());
+    assert!("2023-13-01".parse::<Date>().is_err());
+    assert!("yesterday".parse::<Date>().is_err());
+}
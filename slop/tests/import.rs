@@ -0,0 +1,73 @@
+#![cfg(feature = "serde")]
+
+use expect_test::expect;
+use slop::{compile, import::schema_org};
+
+#[test]
+fn import_schema_org() {
+    let json = r#"{
+  "@context": "https://schema.org",
+  "@graph": [
+    { "@type": "WebPage", "name": "Recipes" },
+    {
+      "@type": ["Recipe"],
+      "name": "Banana Bread",
+      "description": "Moist &amp; easy: a classic.",
+      "recipeYield": ["1", "1 loaf"],
+      "recipeIngredient": [
+        "3 ripe bananas, mashed",
+        "1/3 cup melted butter",
+        "¾ cup sugar",
+        "1 large egg",
+        "1 tsp. baking soda",
+        "2-3 tbsp milk",
+        "1 ½ cups all-purpose flour",
+        "pinch of salt"
+      ],
+      "recipeInstructions": [
+        { "@type": "HowToStep", "text": "Preheat the oven to 350°F." },
+        {
+          "@type": "HowToSection",
+          "name": "Batter",
+          "itemListElement": [
+            { "@type": "HowToStep", "text": "Mix everything in a bowl." },
+            { "@type": "HowToStep", "text": "Pour into a buttered 4x8 inch loaf pan." }
+          ]
+        },
+        { "@type": "HowToStep", "text": "Bake for 1 hour. Cool on a rack." }
+      ]
+    }
+  ]
+}"#;
+    let source = schema_org::import(json).unwrap();
+    expect![[r#"
+        <** Banana Bread ## Moist & easy, a classic.
+        *3: ripe bananas, mashed
+        *1/3 cup: melted butter #+
+        *3/4 cup: sugar #+
+        *1 large: egg #+
+        *1 tsp: baking soda #+
+        *2 tbsp: milk #+
+        *1 1/2 cups: all-purpose flour #+
+        *pinch of salt #Preheat the oven to 350°F =Mix everything in a bowl
+            =Pour into a buttered 4x8 inch loaf pan =Bake for 1 hour. Cool on a rack
+        #*Makes 1 loaf
+        TODO `2-3 tbsp milk` is a range, the lower bound was used instead of 3.
+        TODO the ingredients were all added in the first step, move them to the steps that use them.
+        >
+    "#]]
+    .assert_eq(&source);
+    let (_, errors) = compile(&source);
+    assert!(errors.is_empty(), "{errors}");
+}
+
+#[test]
+fn import_errors() {
+    assert_eq!(
+        schema_org::import(r#"{"@type": "WebPage"}"#)
+            .unwrap_err()
+            .to_string(),
+        "no recipe found"
+    );
+    assert!(schema_org::import("{").is_err());
+}
@@ -0,0 +1,119 @@
+#![cfg(feature = "serde")]
+
+use std::fs;
+
+use expect_test::expect_file;
+use serde_json::Value;
+use slop::{compile, json, SourceFile};
+
+#[test]
+fn cookies() {
+    let (file, _) = compile(&fs::read_to_string("./tests/recipes/cookies.slop").unwrap());
+    let actual = json::to_string(&file);
+    expect_file!["./recipes/expected/cookies.json"].assert_eq(&actual);
+
+    let document: json::Document = serde_json::from_str(&actual).unwrap();
+    assert_eq!(document, json::to_document(&file));
+    assert_eq!(document.version, json::VERSION);
+}
+
+#[test]
+fn semantic_round_trip() {
+    let (file, _) = compile(&fs::read_to_string("./tests/recipes/carrot_pudding.slop").unwrap());
+    let actual: SourceFile = serde_json::from_str(&serde_json::to_string(&file).unwrap()).unwrap();
+    assert_eq!(actual, file);
+}
+
+#[test]
+fn schema() {
+    let schema: serde_json::Value = serde_json::from_str(json::SCHEMA).unwrap();
+    assert_eq!(schema["properties"]["version"]["const"], json::VERSION);
+
+    // The exports, including every kind of node, match the schema.
+    for src in [
+        fs::read_to_string("./tests/recipes/cookies.slop").unwrap(),
+        "<** Errors\n*1 1/2 cups: flour\n*^whey #mix\n*salt\n>\n<\n#mix\n>".to_string(),
+    ] {
+        let (file, _) = compile(&src);
+        let document = serde_json::to_value(json::to_document(&file)).unwrap();
+        assert_eq!(validate(&schema, &schema, &document, ""), Ok(()));
+    }
+}
+
+// Check the value against the subset of JSON Schema used by the schema, returning the path of the
+// first mismatch. Objects may only have the properties the schema lists, so fields added to the
+// format must be added to the schema too.
+fn validate(root: &Value, schema: &Value, value: &Value, path: &str) -> Result<(), String> {
+    let check = |ok: bool, problem: &str| {
+        if ok {
+            Ok(())
+        } else {
+            Err(format!("{path} {problem}: {value}"))
+        }
+    };
+    if let Some(reference) = schema["$ref"].as_str() {
+        let name = reference.strip_prefix("#/$defs/").unwrap();
+        return validate(root, &root["$defs"][name], value, path);
+    }
+    if let Some(variants) = schema["oneOf"].as_array() {
+        let matching = variants
+            .iter()
+            .filter(|variant| validate(root, variant, value, path).is_ok())
+            .count();
+        return check(matching == 1, &format!("matches {matching} variants"));
+    }
+    if let Some(constant) = schema.get("const") {
+        check(value == constant, &format!("is not {constant}"))?;
+    }
+    if let Some(types) = schema.get("type") {
+        let types: Vec<&str> = match types {
+            Value::String(name) => vec![name],
+            types => types
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|name| name.as_str().unwrap())
+                .collect(),
+        };
+        let matches = |name: &str| match name {
+            "null" => value.is_null(),
+            "boolean" => value.is_boolean(),
+            "integer" => value.is_u64() || value.is_i64(),
+            "number" => value.is_number(),
+            "string" => value.is_string(),
+            "array" => value.is_array(),
+            "object" => value.is_object(),
+            _ => panic!("unknown type {name}"),
+        };
+        check(
+            types.iter().any(|name| matches(name)),
+            &format!("is not {types:?}"),
+        )?;
+    }
+    if let Some(minimum) = schema["minimum"].as_f64() {
+        check(
+            value.as_f64() >= Some(minimum),
+            &format!("is less than {minimum}"),
+        )?;
+    }
+    if let Value::Object(object) = value {
+        for key in schema["required"].as_array().into_iter().flatten() {
+            let key = key.as_str().unwrap();
+            check(object.contains_key(key), &format!("has no {key}"))?;
+        }
+        for (key, value) in object {
+            let property = &schema["properties"][key];
+            check(
+                !property.is_null(),
+                &format!("has {key} which is not in the schema"),
+            )?;
+            validate(root, property, value, &format!("{path}.{key}"))?;
+        }
+    }
+    if let Value::Array(items) = value {
+        for (i, item) in items.iter().enumerate() {
+            validate(root, &schema["items"], item, &format!("{path}[{i}]"))?;
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,164 @@
+use expect_test::expect;
+use slop::{
+    compile,
+    config::Config,
+    lint::{self, LintConfig},
+    parse, Diagnostic, Errors, Severity,
+};
+
+const SRC: &str = "<
+*2 cups: flour
+*0 tsp: salt #+
+*1 smidge: pepper #+
+*^crumbs #mix
+*1 cup: flour #+ =+
+>
+";
+
+fn lint(src: &str, config: &LintConfig) -> Vec<Diagnostic> {
+    let (file, _) = compile(src);
+    let (syntax, _) = parse(src);
+    lint::lint(&syntax, &file, config)
+}
+
+#[test]
+fn default_rules() {
+    let diagnostics = Errors::from(lint(SRC, &Config::default().lint));
+    expect![[r#"
+        Found 6 errors:
+        hint[L0006]: recipe has no title at 0..1
+          help: add a title after `<` with `**`
+        warning[L0007]: ingredient `salt` has a quantity of zero at 17..30
+        warning[L0001]: unknown unit `smidge` at 33..51
+        warning[L0004]: derived ingredient `crumbs` is never produced at 54..63
+          note: `^` marks an ingredient as the by product of a previous step
+        hint[L0003]: ingredient `flour` is listed more than once at 68..82
+        warning[L0002]: `+` used as unary operator text at 85..88
+          help: use `#+` to combine two operands
+    "#]]
+    .assert_eq(&diagnostics.to_string());
+}
+
+#[test]
+fn unary_plus_under_binary() {
+    // The `+` is merged into `mix` by the semantic tree, but is still a unary `+`.
+    let diagnostics = Errors::from(lint("<** Mix\n*a =+ *b #mix\n>", &Config::default().lint));
+    expect![[r#"
+        Found 1 errors:
+        warning[L0002]: `+` used as unary operator text at 11..14
+          help: use `#+` to combine two operands
+    "#]]
+    .assert_eq(&diagnostics.to_string());
+}
+
+#[test]
+fn configured_rules() {
+    let config = Config::from_toml(
+        r#"
+[lint]
+missing-title = "error"
+unknown-unit = "off"
+zero-quantity = "hint"
+"#,
+    )
+    .unwrap();
+    let diagnostics = lint(SRC, &config.lint);
+    let codes: Vec<_> = diagnostics
+        .iter()
+        .map(|d| (d.code.as_str(), d.severity))
+        .collect();
+    assert_eq!(
+        codes,
+        [
+            ("L0006", Severity::Error),
+            ("L0007", Severity::Hint),
+            ("L0004", Severity::Warning),
+            ("L0003", Severity::Hint),
+            ("L0002", Severity::Warning),
+        ]
+    );
+}
+
+#[test]
+fn invalid_config() {
+    let errors: Vec<String> = [
+        "[lint]\nno-such-rule = \"off\"",
+        "[lint]\nunknown-unit = \"loud\"",
+        "lint = 1",
+    ]
+    .iter()
+    .map(|src| Config::from_toml(src).unwrap_err().to_string())
+    .collect();
+    expect![[r#"
+        [
+            "unknown lint rule `no-such-rule`",
+            "invalid level for lint rule `unknown-unit`, expected one of \"off\", \"hint\", \"warning\" or \"error\"",
+            "`lint` must be a table",
+        ]
+    "#]]
+    .assert_debug_eq(&errors);
+}
+
+#[test]
+fn unit_consistency() {
+    let src = "<** Butter
+*1 cup: butter
+*2: eggs #+
+*4 oz: butter #+
+*1 cup: eggs #+
+*40 tsp: sugar #+
+*1 tbps: salt #+
+*3 cups: flour #+
+*1 lbs: flour #+
+*1 pint: milk #+
+*1 cup: milk #+
+*1 Pound: sugar #+ =mix
+>
+";
+    let diagnostics = Errors::from(lint(src, &Config::default().lint));
+    expect![[r#"
+        Found 12 errors:
+        hint[L0003]: ingredient `butter` is listed more than once at 38..52
+        warning[L0009]: `butter` is measured by both volume and mass at 38..52
+          note: amounts can only be totaled when they are measured the same way
+        hint[L0003]: ingredient `eggs` is listed more than once at 55..68
+        warning[L0009]: `eggs` is measured by both count and volume at 55..68
+          note: amounts can only be totaled when they are measured the same way
+        warning[L0010]: 40 tsp of `sugar` is an unusually large amount at 71..86
+          help: check the quantity, or measure in `tbsp`
+        warning[L0001]: unknown unit `tbps` at 89..103
+          help: did you mean `tbsp`?
+        hint[L0003]: ingredient `flour` is listed more than once at 124..138
+        warning[L0009]: `flour` is measured by both volume and mass at 124..138
+          note: amounts can only be totaled when they are measured the same way
+        hint[L0003]: ingredient `milk` is listed more than once at 158..171
+        hint[L0003]: ingredient `sugar` is listed more than once at 174..190
+        hint[L0008]: unit `Pound` is usually written `lbs` at 174..190
+          help: replace `Pound` with `lbs`
+        warning[L0009]: `sugar` is measured by both volume and mass at 174..190
+          note: amounts can only be totaled when they are measured the same way
+    "#]]
+    .assert_eq(&diagnostics.to_string());
+}
+
+#[test]
+fn example_recipes_have_no_warnings() {
+    let config = Config::discover(std::path::Path::new("./tests/recipes")).unwrap();
+    let recipes = [
+        "carrot_pudding",
+        "cookies",
+        "hauloumi",
+        "keiserschmarrn",
+        "salted_butter",
+        "simple",
+        "souffle",
+    ];
+    for name in recipes {
+        let src = std::fs::read_to_string(format!("./tests/recipes/{name}.slop")).unwrap();
+        let diagnostics = Errors::from(lint(&src, &config.lint));
+        assert!(
+            diagnostics.iter().all(|d| d.severity == Severity::Hint),
+            "{name}: {diagnostics}"
+        );
+    }
+}
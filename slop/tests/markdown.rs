@@ -0,0 +1,39 @@
+use expect_test::expect;
+use slop::{compile, markdown::to_markdown};
+
+#[test]
+fn recipe_to_markdown() {
+    let (file, errors) = compile(
+        "<** Pancakes ## Thin | French style.
+*1 cup: flour
+*2: eggs #whisk
+*1 1/4 cups: milk #+ =rest 30m
+#*Makes 8 crepes.
+Keeps for a day.
+>",
+    );
+    assert!(errors.0.is_empty());
+    expect![[r#"
+        # Pancakes
+
+        Thin | French style.
+
+        ## Ingredients
+
+        | Quantity | Unit | Ingredient |
+        | ---: | --- | --- |
+        | 1 | cup | flour |
+        | 2 |  | eggs |
+        | 1 1/4 | cups | milk |
+
+        ## Instructions
+
+        1. Whisk the flour and eggs.
+        2. Add the milk.
+        3. Rest 30m.
+
+        > Makes 8 crepes.
+        > Keeps for a day.
+    "#]]
+    .assert_eq(&to_markdown(&file.recipes()[0]));
+}
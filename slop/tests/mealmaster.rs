@@ -0,0 +1,86 @@
+use expect_test::expect;
+use slop::{compile, import::mealmaster};
+
+const COLLECTION: &str = "\
+MMMMM----- Recipe via Meal-Master (tm) v8.05
+
+      Title: Banana Bread
+ Categories: Breads, Fruits
+      Yield: 1 loaf
+
+      3 md Bananas; mashed
+    1/3 c  Butter, melted
+    3/4 c  Sugar
+      1    Egg
+      1 ts Baking soda
+           Salt
+  1 1/2 c  All-purpose flour
+           -sifted
+
+  Preheat oven to 350 degrees F.
+
+  Mix the butter into the mashed bananas. Mix in the sugar, egg and
+  baking soda.
+
+  Mix in the flour and salt. Bake for 1 hour.
+
+MMMMM
+
+----- Recipe via Meal-Master (tm) v8.05
+
+      Title: Garlic Butter
+ Categories: Sauces
+   Servings: 8
+
+    1/2 c  Butter, softened                    2    Garlic cloves; minced
+      1 tb Parsley; chopped
+
+  Mash everything together. Chill until firm.
+
+-----
+";
+
+#[test]
+fn import_mealmaster() {
+    let recipes = mealmaster::import(COLLECTION).unwrap();
+    let names: Vec<&str> = recipes.iter().map(|r| r.name.as_str()).collect();
+    assert_eq!(names, ["Banana Bread", "Garlic Butter"]);
+    let sources: Vec<&str> = recipes
+        .iter()
+        .map(|r| r.result.as_deref().unwrap())
+        .collect();
+    for source in &sources {
+        assert!(compile(source).1 .0.is_empty());
+    }
+    expect![[r#"
+        <** Banana Bread
+        *3 medium: Bananas, mashed
+        *1/3 cup: Butter, melted #+
+        *3/4 cup: Sugar #+
+        *1: Egg #+
+        *1 tsp: Baking soda #+
+        *Salt #+
+        *1 1/2 cup: All-purpose flour sifted #Preheat oven to 350 degrees F
+            =Mix the butter into the mashed bananas. Mix in the sugar, egg and baking soda
+            =Mix in the flour and salt. Bake for 1 hour
+        #*Makes 1 loaf
+        TODO the ingredients were all added in the first step, move them to the steps that use them.
+        >
+
+        <** Garlic Butter
+        *1/2 cup: Butter, softened
+        *2: Garlic cloves, minced #+
+        *1 tbsp: Parsley, chopped #Mash everything together. Chill until firm
+        #*Makes 8
+        >
+    "#]]
+    .assert_eq(&sources.join("\n"));
+}
+
+#[test]
+fn import_mealmaster_errors() {
+    assert!(matches!(
+        mealmaster::import("just some text"),
+        Err(slop::import::Error::NoRecipe)
+    ));
+}
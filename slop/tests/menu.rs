@@ -0,0 +1,120 @@
+use expect_test::expect;
+use slop::{
+    compile,
+    menu::{consolidate_prep, schedule, Clock},
+};
+
+#[test]
+fn schedule_menu() {
+    let (file, errors) = compile(
+        "<** Roast
+*1: chicken =season 5m
+=roast 400F 1h
+=rest 10m
+>
+<** Salad
+*1 head: lettuce =wash 5m
+*1 tbsp: dressing #toss 2m
+>
+<** Rice
+*1 cup: rice
+*2 cups: water #simmer 20m
+=fluff 1m
+>
+",
+    );
+    assert!(errors.is_empty(), "{errors}");
+    let serve: Clock = "18:30".parse().unwrap();
+    let plan = schedule(file.recipes(), serve);
+    expect![[r#"
+        17:15-17:20 Roast: season 5m
+        17:20-18:20 Roast: roast 400F 1h (passive)
+        18:09-18:29 Rice: simmer 20m (passive)
+        18:20-18:30 Roast: rest 10m (passive)
+        18:22-18:27 Salad: wash 5m
+        18:27-18:29 Salad: toss 2m
+        18:29-18:30 Rice: fluff 1m
+        18:30 serve
+    "#]]
+    .assert_eq(&plan.to_string());
+}
+
+#[test]
+fn parse_clock() {
+    let parse = |s: &str| s.parse::<Clock>().map(|clock| clock.to_string());
+    assert_eq!(parse("18:30"), Ok("18:30".to_string()));
+    assert_eq!(parse("6:30pm"), Ok("18:30".to_string()));
+    assert_eq!(parse("12 am"), Ok("00:00".to_string()));
+    assert_eq!(parse("7"), Ok("07:00".to_string()));
+    assert!(parse("25:00").is_err());
+    assert!(parse("13pm").is_err());
+    assert_eq!(
+        (Clock::new(1, 0) - std::time::Duration::from_secs(7200)).to_string(),
+        "23:00 (-1d)"
+    );
+}
+
+#[test]
+fn consolidate_batch_prep() {
+    let (file, errors) = compile(
+        "<** Soup
+*2: onions =chop
+*1 tbsp: oil #saute 10m
+*1 qt: stock #simmer 30m
+>
+<** Chili
+*1: Onion =chop
+*1 lb: beef #brown
+*2: tomatoes =chop #simmer 1h
+>
+<** Salsa
+*1: tomato =chop
+*1: red onion =chop #mix
+>
+",
+    );
+    assert!(errors.is_empty(), "{errors}");
+    let batches: Vec<String> = consolidate_prep(file.recipes())
+        .iter()
+        .map(|batch| batch.to_string())
+        .collect();
+    expect![[r#"
+        [
+            "chop: 3.00 onions for Soup (saute 10m), Chili (brown)",
+            "chop: 3.00 tomatoes for Chili (simmer 1h), Salsa (mix)",
+            "chop: 1.00 red onion for Salsa (mix)",
+        ]
+    "#]]
+    .assert_debug_eq(&batches);
+}
+
+#[test]
+fn consolidate_batch_prep_names() {
+    let (file, errors) = compile(
+        "<** Dip
+*1 cup: Hummus =stir
+*1 tbsp: molasses =warm #+
+*1 tsp: cress =rinse #serve
+>
+<** Sides
+*1 cup: hummus =stir
+*2 tsp: Molasses =warm #+
+*1 bunch: asparagus =trim #grill
+>
+",
+    );
+    assert!(errors.is_empty(), "{errors}");
+    let batches: Vec<String> = consolidate_prep(file.recipes())
+        .iter()
+        .map(|batch| batch.to_string())
+        .collect();
+    expect![[r#"
+        [
+            "stir: 2.00 cup Hummus for Dip (serve), Sides (grill)",
+            "warm: 1.67 tbsp molasses for Dip (serve), Sides (grill)",
+            "rinse: 1.00 tsp cress for Dip (serve)",
+            "trim: 1.00 bunch asparagus for Sides (grill)",
+        ]
+    "#]]
+    .assert_debug_eq(&batches);
+}
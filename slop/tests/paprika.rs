@@ -0,0 +1,33 @@
+#![cfg(feature = "paprika")]
+
+use expect_test::expect;
+use slop::{compile, import::paprika};
+
+#[test]
+fn import_paprika_archive() {
+    let archive = std::fs::read("./tests/paprika/recipes.paprikarecipes").unwrap();
+    let recipes = paprika::import(&archive).unwrap();
+    let names: Vec<&str> = recipes.iter().map(|r| r.name.as_str()).collect();
+    assert_eq!(names, ["Pancakes", "Toast", "Broken"]);
+    let source = recipes[0].result.as_ref().unwrap();
+    assert!(compile(source).1 .0.is_empty());
+    expect![[r#"
+        <** Pancakes ## Fluffy weekend pancakes.
+        *1 1/2 cups: flour
+        *1 tbsp: sugar #+
+        *2: eggs #+
+        *1 cup: milk #Whisk the flour and sugar =Beat in the eggs and milk
+            =Cook on a hot griddle for 2 minutes per side
+        #*Makes 4 pancakes
+        TODO the ingredients were all added in the first step, move them to the steps that use them.
+        >
+    "#]]
+    .assert_eq(source);
+    expect!["reading recipe: invalid gzip header"]
+        .assert_eq(&recipes[2].result.as_ref().unwrap_err().to_string());
+}
+
+#[test]
+fn import_paprika_errors() {
+    assert!(paprika::import(b"not an archive").is_err());
+}
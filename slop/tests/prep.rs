@@ -0,0 +1,42 @@
+use expect_test::expect;
+use slop::{compile, prep::prep_list, to_svgs};
+
+const SRC: &str = "<** Stew
+*1: onion =peel =dice
+*2: carrots =peel =dice #saute 5m
+*1 tbsp: butter =melt #+
+*1 qt: stock #simmer 30m
+>
+";
+
+#[test]
+fn mise_en_place() {
+    let (file, errors) = compile(SRC);
+    assert!(errors.is_empty(), "{errors}");
+    let lines: Vec<String> = prep_list(&file.recipes[0])
+        .iter()
+        .map(|group| group.to_string())
+        .collect();
+    expect![[r#"
+        [
+            "peel, dice: 1 onion (for saute 5m), 2 carrots (for saute 5m)",
+            "melt: 1 tbsp butter (for simmer 30m)",
+        ]
+    "#]]
+    .assert_debug_eq(&lines);
+}
+
+#[test]
+fn whole_recipe_is_not_prep() {
+    let (file, errors) = compile("<\n*1: chicken =season =roast 1h\n>");
+    assert!(errors.is_empty(), "{errors}");
+    assert!(prep_list(&file.recipes[0]).is_empty());
+}
+
+#[test]
+fn prep_on_card() {
+    let (svgs, errors) = to_svgs(SRC);
+    assert!(errors.is_empty(), "{errors}");
+    assert!(svgs[0].contains("Mise en place"));
+    assert!(svgs[0].contains("- peel, dice: 1 onion (for saute 5m), 2 carrots (for saute 5m)"));
+}
@@ -0,0 +1,164 @@
+use expect_test::expect;
+use slop::{
+    compile,
+    prose::{render, Style},
+};
+
+#[test]
+fn souffle_as_text() {
+    let src = std::fs::read_to_string("tests/recipes/souffle.slop").unwrap();
+    let (file, errors) = compile(&src);
+    assert!(errors.is_empty(), "{errors}");
+    expect![[r#"
+        Souffle pancake with one egg
+
+        Ingredients
+        - 3 or 4 drops lemon juice
+        - 1 egg
+        - 1 1/2 tbsp sugar
+        - 2 tbsp flour
+        - 1 tbsp milk
+        - 1/2 tsp vanilla
+        - 1 tsp oil
+        - 2 tsp water
+        - 1 tsp water
+
+        Instructions
+        1. Separate the egg, keep white.
+        2. Stir in the lemon juice.
+        3. Beat at medium speed, until foamy.
+        4. Sprinkle in the sugar.
+        5. Beat at medium speed 3m until firm peaks form.
+        6. Mix the egg yolk, flour and milk to combine.
+        7. Stir in the vanilla.
+        8. Mix the egg white mixture with circular motion.
+        9. Fold in the result of step 5 with flat spatula.
+        10. Heat the oil in pan 1m.
+        11. Scoop the pancake mixture into pan as two pancakes.
+        12. Add the water to sides of pan.
+        13. Cover cook 2m on medium heat.
+        14. Add the water to sides of pan.
+        15. Place the result of step 9 on top.
+        16. Cover cook 5m on medium low heat.
+        17. Flip.
+        18. Cover cook 5m.
+        19. Serve with fruit and syrup/powdered sugar.
+
+        Makes 2 pancakes
+    "#]]
+    .assert_eq(&render(&file.recipes[0], Style::Text));
+}
+
+#[test]
+fn cookies_as_markdown() {
+    let src = std::fs::read_to_string("tests/recipes/cookies.slop").unwrap();
+    let (file, errors) = compile(&src);
+    assert!(errors.is_empty(), "{errors}");
+    expect![[r#"
+        ## Ingredients
+
+        | Quantity | Unit | Ingredient |
+        | ---: | --- | --- |
+        |  |  | butter |
+        |  |  | sugar |
+        |  |  | brown sugar |
+        |  |  | vanilla |
+        |  |  | eggs |
+        |  |  | flour |
+        |  |  | soda |
+        |  |  | salt |
+        |  |  | chocolate chips |
+        |  |  | chopped nuts |
+
+        ## Instructions
+
+        1. Soften the butter.
+        2. Beat the sugar, brown sugar and vanilla.
+        3. Beat the eggs, one at a time.
+        4. Mix the flour, soda and salt.
+        5. Beat the result of step 3, slowly.
+        6. Stir the chocolate chips and chopped nuts.
+        7. Form into balls.
+        8. Bake 375F 10m.
+
+        > Yield 1 dozen cookies
+    "#]]
+    .assert_eq(&render(&file.recipes[0], Style::Markdown));
+}
+
+#[test]
+fn escape_html() {
+    let (file, errors) =
+        compile("<** Mac & cheese\n*1 cup: macaroni =boil until \"al dente\" & drain\n>");
+    assert!(errors.is_empty(), "{errors}");
+    expect![[r#"
+        <article class="recipe" id="recipe">
+        <h1>Mac &amp; cheese</h1>
+        <table class="ingredients">
+        <caption>Ingredients</caption>
+        <thead>
+        <tr><th scope="col">Quantity</th><th scope="col">Unit</th><th scope="col">Ingredient</th></tr>
+        </thead>
+        <tbody>
+        <tr><td>1</td><td>cup</td><td>macaroni</td></tr>
+        </tbody>
+        </table>
+        <h2>Instructions</h2>
+        <ol>
+        <li>Boil the macaroni until &quot;al dente&quot; &amp; drain.</li>
+        </ol>
+        <table class="card">
+        <caption>Recipe card</caption>
+        <tbody>
+        <tr><th scope="row" id="recipe-cell-1">1 cup macaroni</th><td id="recipe-cell-2" headers="recipe-cell-1">boil until &quot;al dente&quot; &amp; drain</td></tr>
+        </tbody>
+        </table>
+        </article>
+    "#]]
+    .assert_eq(&render(&file.recipes[0], Style::Html));
+}
+
+#[test]
+fn combine_steps() {
+    let render = |src: &str| {
+        let (file, errors) = compile(src);
+        assert!(errors.is_empty(), "{errors}");
+        render(&file.recipes[0], Style::Text)
+    };
+    expect![[r#"
+        Mix
+
+        Ingredients
+        - a
+        - b
+
+        Instructions
+        1. Combine the a and b.
+        2. Mix.
+    "#]]
+    .assert_eq(&render("<** Mix\n*a *b #+ =mix\n>"));
+    expect![[r#"
+        Root
+
+        Ingredients
+        - a
+        - b
+        - c
+
+        Instructions
+        1. Combine the a, b and c.
+    "#]]
+    .assert_eq(&render("<** Root\n*a *b #+ *c #+\n>"));
+    expect![[r#"
+        Add
+
+        Ingredients
+        - a
+        - b
+
+        Instructions
+        1. Chop the a.
+        2. Add the b.
+    "#]]
+    .assert_eq(&render("<** Add\n*a =chop *b #+\n>"));
+}
@@ -0,0 +1,72 @@
+#![cfg(feature = "serde")]
+
+use expect_test::expect;
+use slop::{compile, import, schema_org};
+
+const SOURCE: &str = "<** Roast Potatoes ## Crisp outside, fluffy inside.
+*1 kg: potatoes =peel 5m =boil 10m
+*3 tbsp: oil #toss =roast 45m
+#*Serves 4
+>
+";
+
+#[test]
+fn export_schema_org() {
+    let (file, errors) = compile(SOURCE);
+    assert!(errors.0.is_empty());
+    expect![[r#"
+        {
+          "@context": "https://schema.org",
+          "@type": "Recipe",
+          "name": "Roast Potatoes",
+          "description": "Crisp outside, fluffy inside.",
+          "recipeYield": "4",
+          "recipeIngredient": [
+            "1 kg potatoes",
+            "3 tbsp oil"
+          ],
+          "recipeInstructions": [
+            {
+              "@type": "HowToStep",
+              "position": 1,
+              "text": "Peel the potatoes 5m."
+            },
+            {
+              "@type": "HowToStep",
+              "position": 2,
+              "text": "Boil 10m."
+            },
+            {
+              "@type": "HowToStep",
+              "position": 3,
+              "text": "Toss the oil."
+            },
+            {
+              "@type": "HowToStep",
+              "position": 4,
+              "text": "Roast 45m."
+            }
+          ],
+          "prepTime": "PT5M",
+          "cookTime": "PT55M",
+          "totalTime": "PT1H"
+        }"#]]
+    .assert_eq(&schema_org::to_string(&file));
+}
+
+#[test]
+fn export_round_trip() {
+    let (file, _) = compile(SOURCE);
+    let imported = import::schema_org::import(&schema_org::to_string(&file)).unwrap();
+    let (imported, errors) = compile(&imported);
+    assert!(errors.0.is_empty());
+    let recipe = &imported.recipes()[0];
+    assert_eq!(recipe.title(), Some("Roast Potatoes"));
+    assert_eq!(recipe.recipe_yield(), Some("4"));
+    let ingredients: Vec<String> = recipe
+        .ingredients()
+        .iter()
+        .map(ToString::to_string)
+        .collect();
+    assert_eq!(ingredients, ["1 kg potatoes", "3 tbsp oil"]);
+}
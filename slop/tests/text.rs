@@ -0,0 +1,142 @@
+use expect_test::expect;
+use slop::{compile, html, import::text, markdown, prose};
+
+const COOKIES: &str = "Chocolate Chip Cookies
+Chewy in the middle.
+Makes 24 cookies
+
+Ingredients:
+- 1 cup butter, softened
+- 3/4 cup sugar
+- 3/4 cup brown sugar
+- 2 eggs
+- 2 1/4 cups all-purpose flour
+- 1 tsp baking soda
+- salt
+- 2 cups chocolate chips
+
+Directions:
+1. Preheat the oven to 375F.
+2. Cream the butter, sugar and brown sugar. Beat in the eggs.
+3. Whisk the flour and baking soda in another bowl. Stir into the butter mixture.
+4. Fold in the chips and bake for 10 minutes.
+";
+
+fn import(text: &str) -> String {
+    let source = text::import(text).unwrap();
+    let (_, errors) = compile(&source);
+    assert!(errors.0.is_empty(), "{errors}\n{source}");
+    source
+}
+
+#[test]
+fn import_text_with_headings() {
+    let source = import(COOKIES);
+    expect![[r#"
+        <** Chocolate Chip Cookies ## Chewy in the middle.
+        *salt =Preheat the oven to 375F
+        *1 cup: butter, softened #+
+        *3/4 cup: sugar #+
+        *3/4 cup: brown sugar #Cream the butter, sugar and brown sugar
+        *2: eggs #Beat in the eggs
+        *2 1/4 cups: all-purpose flour #+
+        *1 tsp: baking soda #Whisk the flour and baking soda in another bowl
+            =Stir into the butter mixture
+        *2 cups: chocolate chips #Fold in the chips and bake for 10 minutes
+        #*Makes 24 cookies
+        TODO step 3 was applied to the result of the step before although it says "Whisk the flour and baking soda in another bowl".
+        TODO all-purpose flour was guessed to be added in step 3 as it mentions flour.
+        TODO chocolate chips was guessed to be added in step 4 as it mentions chip.
+        TODO no step mentions salt, added in the first step.
+        >
+    "#]]
+    .assert_eq(&source);
+}
+
+#[test]
+fn render_imported_text_without_notes() {
+    let source = import(COOKIES);
+    let (file, _) = compile(&source);
+    let recipe = &file.recipes()[0];
+    assert_eq!(recipe.recipe_yield(), Some("24 cookies"));
+    #[allow(unused_mut)]
+    let mut rendered = vec![
+        html::to_html(file.recipes()),
+        markdown::to_markdown(recipe),
+        prose::render(recipe, prose::Style::Text),
+        slop::to_svgs(&source).0.join("\n"),
+    ];
+    #[cfg(feature = "serde")]
+    rendered.push(slop::schema_org::to_string(&file));
+    for rendered in rendered {
+        assert!(rendered.contains("Chewy in the middle"), "{rendered}");
+        assert!(rendered.contains("24 cookies"), "{rendered}");
+        assert!(!rendered.contains("TODO"), "{rendered}");
+    }
+}
+
+#[test]
+fn import_text_without_headings() {
+    let source = import(
+        "Toast
+
+2 slices bread
+1 tbsp butter
+
+Toast the bread until golden.
+Spread with butter and serve.
+",
+    );
+    expect![[r#"
+        <** Toast
+        *2 slices: bread =Toast the bread until golden
+        *1 tbsp: butter #Spread with butter and serve
+        >
+    "#]]
+    .assert_eq(&source);
+}
+
+#[test]
+fn import_text_title_with_number() {
+    let with_headings = import(
+        "7 Layer Dip
+
+Ingredients:
+- 1 can refried beans
+- 2 cups cheese
+
+Directions:
+Spread the refried beans in a dish. Top with the cheese.
+",
+    );
+    let without_headings = import(
+        "3 Bean Salad
+A summer side.
+
+1 can kidney beans
+1 can green beans
+
+Toss the kidney beans and green beans.
+",
+    );
+    expect![[r#"
+        <** - 7 Layer Dip
+        *1 can: refried beans =Spread the refried beans in a dish
+        *2 cups: cheese #Top with the cheese
+        >
+
+        <** - 3 Bean Salad ## A summer side.
+        *1 can: kidney beans
+        *1 can: green beans #Toss the kidney beans and green beans
+        >
+    "#]]
+    .assert_eq(&(with_headings + "\n" + &without_headings));
+}
+
+#[test]
+fn import_text_errors() {
+    assert!(matches!(
+        text::import("\n\n"),
+        Err(slop::import::Error::NoRecipe)
+    ));
+}
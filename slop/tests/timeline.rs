@@ -0,0 +1,53 @@
+use expect_test::expect;
+use slop::{compile, timeline::timeline, to_timeline_svgs};
+
+const SRC: &str = "<** Pancakes
+*1 cup: flour
+*1 cup: milk #whisk 2m
+*1: egg #+ =rest 30m
+*1 tbsp: butter =heat in pan 1m
+#cook 3m on medium heat
+>
+";
+
+#[test]
+fn critical_path() {
+    let (file, errors) = compile(SRC);
+    assert!(errors.is_empty(), "{errors}");
+    let timeline = timeline(&file.recipes[0]);
+    expect![[r#"
+        total 35m
+               0s        2m * whisk 2m
+               2m       32m * rest 30m (passive)
+               0s        1m   heat in pan 1m
+              32m       35m * cook 3m on medium heat
+    "#]]
+    .assert_eq(&timeline.to_string());
+    let critical: Vec<&str> = timeline.critical_path().map(|step| step.text()).collect();
+    assert_eq!(critical, ["whisk 2m", "rest 30m", "cook 3m on medium heat"]);
+
+    // A `+` at the root groups the last steps without being one.
+    let (file, errors) = compile("<** Plate\n*rice =boil 15m\n*greens =steam 5m #+\n>");
+    assert!(errors.is_empty(), "{errors}");
+    expect![[r#"
+        total 15m
+               0s       15m * boil 15m (passive)
+               0s        5m   steam 5m (passive)
+    "#]]
+    .assert_eq(&slop::timeline::timeline(&file.recipes[0]).to_string());
+}
+
+#[test]
+fn gantt_svg() {
+    let (svgs, errors) = to_timeline_svgs(SRC);
+    assert!(errors.is_empty(), "{errors}");
+    assert_eq!(svgs.len(), 1);
+    for text in [
+        "Pancakes (35m)",
+        "heat in pan 1m",
+        "active critical",
+        "passive",
+    ] {
+        assert!(svgs[0].contains(text), "missing {text}");
+    }
+}
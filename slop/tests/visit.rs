@@ -0,0 +1,201 @@
+use expect_test::expect;
+use slop::{compile, parse, visit};
+
+// Every kind of operand: quantities, derived ingredients, unary and binary operators, operands
+// left unused at the end of a recipe and operands missing from an operator.
+const SRC: &str = "<** Kinds
+*1 1/2 cups: flour
+*2: eggs #whisk
+=rest
+*^whey
+*salt
+>
+<** Missing
+#mix
+>
+";
+
+fn ast_label(op: &slop::ast::Operand) -> String {
+    match op {
+        slop::ast::Operand::Ingredient { text, .. } => format!("ingredient {text}"),
+        slop::ast::Operand::UnaryOp { text, .. } => format!("unary {text}"),
+        slop::ast::Operand::BinaryOp { text, .. } => format!("binary {text}"),
+        slop::ast::Operand::MissingOperand { .. } => "missing".to_string(),
+        slop::ast::Operand::UnusedOperands { .. } => "unused".to_string(),
+        _ => unreachable!(),
+    }
+}
+
+fn quantity_label(quantity: &slop::ast::Quantity) -> String {
+    match quantity {
+        slop::ast::Quantity::Number(n) => format!("quantity {n}"),
+        slop::ast::Quantity::Fraction(f) => format!("quantity {f}"),
+        _ => unreachable!(),
+    }
+}
+
+#[derive(Default)]
+struct AstVisitor(Vec<String>);
+
+impl<'a> visit::ast::Visit<'a> for AstVisitor {
+    fn visit_recipe(&mut self, recipe: &'a slop::ast::Recipe) {
+        self.0
+            .push(format!("recipe {}", recipe.title.as_deref().unwrap_or("")));
+        visit::ast::walk_recipe(self, recipe)
+    }
+    fn visit_operand(&mut self, op: &'a slop::ast::Operand) {
+        self.0.push(ast_label(op));
+        visit::ast::walk_operand(self, op)
+    }
+    fn visit_quantity(&mut self, quantity: &'a slop::ast::Quantity) {
+        self.0.push(quantity_label(quantity));
+    }
+}
+
+impl visit::ast::VisitMut for AstVisitor {
+    fn visit_recipe_mut(&mut self, recipe: &mut slop::ast::Recipe) {
+        self.0
+            .push(format!("recipe {}", recipe.title.as_deref().unwrap_or("")));
+        visit::ast::walk_recipe_mut(self, recipe)
+    }
+    fn visit_operand_mut(&mut self, op: &mut slop::ast::Operand) {
+        self.0.push(ast_label(op));
+        visit::ast::walk_operand_mut(self, op)
+    }
+    fn visit_quantity_mut(&mut self, quantity: &mut slop::ast::Quantity) {
+        self.0.push(quantity_label(quantity));
+    }
+}
+
+impl visit::ast::Fold for AstVisitor {
+    fn fold_operand(&mut self, op: slop::ast::Operand) -> slop::ast::Operand {
+        self.0.push(ast_label(&op));
+        visit::ast::fold_operand(self, op)
+    }
+    fn fold_quantity(&mut self, quantity: slop::ast::Quantity) -> slop::ast::Quantity {
+        self.0.push(quantity_label(&quantity));
+        quantity
+    }
+}
+
+#[test]
+fn walk_ast() {
+    use visit::ast::{Fold, Visit, VisitMut};
+
+    let (file, _) = parse(SRC);
+    let mut visitor = AstVisitor::default();
+    visitor.visit_source_file(&file);
+    expect![[r#"
+        recipe Kinds
+        unused
+        unary rest
+        binary whisk
+        ingredient flour
+        quantity 1
+        quantity 1/2
+        ingredient eggs
+        quantity 2
+        ingredient whey
+        ingredient salt
+        recipe Missing
+        binary mix
+        missing
+        missing"#]]
+    .assert_eq(&visitor.0.join("\n"));
+
+    let (mut mutable, _) = parse(SRC);
+    let mut visitor_mut = AstVisitor::default();
+    visitor_mut.visit_source_file_mut(&mut mutable);
+    assert_eq!(visitor_mut.0, visitor.0);
+
+    // Folding without changes rebuilds the same tree.
+    let (folded, _) = parse(SRC);
+    let mut folder = AstVisitor::default();
+    let folded = folder.fold_source_file(folded);
+    assert_eq!(folded, file);
+    let visited: Vec<&String> = visitor
+        .0
+        .iter()
+        .filter(|label| !label.starts_with("recipe"))
+        .collect();
+    assert_eq!(folder.0.iter().collect::<Vec<_>>(), visited);
+}
+
+fn semantic_label(op: &slop::Operand) -> String {
+    match op {
+        slop::Operand::Ingredient(_) => "ingredient".to_string(),
+        slop::Operand::Operator { text, .. } => format!("operator {text}"),
+        slop::Operand::MissingOperand { .. } => "missing".to_string(),
+        slop::Operand::UnusedOperands { .. } => "unused".to_string(),
+        _ => unreachable!(),
+    }
+}
+
+#[derive(Default)]
+struct SemanticVisitor(Vec<String>);
+
+impl<'a> visit::semantic::Visit<'a> for SemanticVisitor {
+    fn visit_operand(&mut self, op: &'a slop::Operand) {
+        self.0.push(semantic_label(op));
+        visit::semantic::walk_operand(self, op)
+    }
+    fn visit_ingredient(&mut self, ingredient: &'a slop::Ingredient) {
+        self.0.push(format!("  {ingredient}"));
+    }
+}
+
+impl visit::semantic::VisitMut for SemanticVisitor {
+    fn visit_operand_mut(&mut self, op: &mut slop::Operand) {
+        self.0.push(semantic_label(op));
+        visit::semantic::walk_operand_mut(self, op)
+    }
+    fn visit_ingredient_mut(&mut self, ingredient: &mut slop::Ingredient) {
+        self.0.push(format!("  {ingredient}"));
+    }
+}
+
+impl visit::semantic::Fold for SemanticVisitor {
+    fn fold_operand(&mut self, op: slop::Operand) -> slop::Operand {
+        self.0.push(semantic_label(&op));
+        visit::semantic::fold_operand(self, op)
+    }
+    fn fold_ingredient(&mut self, ingredient: slop::Ingredient) -> slop::Ingredient {
+        self.0.push(format!("  {ingredient}"));
+        ingredient
+    }
+}
+
+#[test]
+fn walk_semantic() {
+    use visit::semantic::{Fold, Visit, VisitMut};
+
+    let (file, _) = compile(SRC);
+    let mut visitor = SemanticVisitor::default();
+    visitor.visit_source_file(&file);
+    expect![[r#"
+        unused
+        operator rest
+        operator whisk
+        ingredient
+          1 1/2 cups flour
+        ingredient
+          2 eggs
+        ingredient
+          whey
+        ingredient
+          salt
+        operator mix
+        missing
+        missing"#]]
+    .assert_eq(&visitor.0.join("\n"));
+
+    let mut mutable = file.clone();
+    let mut visitor_mut = SemanticVisitor::default();
+    visitor_mut.visit_source_file_mut(&mut mutable);
+    assert_eq!(visitor_mut.0, visitor.0);
+
+    // Folding without changes rebuilds the same tree.
+    let mut folder = SemanticVisitor::default();
+    assert_eq!(folder.fold_source_file(file.clone()), file);
+    assert_eq!(folder.0, visitor.0);
+}